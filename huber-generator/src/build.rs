@@ -301,7 +301,9 @@ async fn update_description(pkg: &mut Package) -> anyhow::Result<()> {
         .personal_token(env::var("GITHUB_TOKEN")?)
         .build()?;
 
-    let PackageSource::Github { owner, repo } = &pkg.source;
+    let PackageSource::Github { owner, repo } = &pkg.source else {
+        return Ok(());
+    };
     let repo = octocrab.repos(owner, repo).get().await?;
     pkg.description = repo.description;
 
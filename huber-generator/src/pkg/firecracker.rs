@@ -15,12 +15,14 @@ pub fn release() -> Package {
                 executable_mappings: None,
                 tag_version_regex_template: None,
                 scan_dirs: Some(vec!["release-v{version}-{arch}".to_string()]),
+                ..Default::default()
             }),
             PackageTargetType::LinuxArm64(PackageManagement {
                 artifact_templates: vec!["firecracker-v{version}-aarch64.tgz".to_string()],
                 executable_mappings: None,
                 tag_version_regex_template: None,
                 scan_dirs: Some(vec!["release-v{version}-{arch}".to_string()]),
+                ..Default::default()
             }),
         ],
         ..Default::default()
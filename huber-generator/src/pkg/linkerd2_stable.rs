@@ -17,6 +17,7 @@ pub fn release() -> Package {
                 }),
                 tag_version_regex_template: Some(r"^stable-(\d+.\d+.\d+)$".to_string()),
                 scan_dirs: None,
+                ..Default::default()
             }),
             PackageTargetType::LinuxArm64(PackageManagement {
                 artifact_templates: vec!["linkerd2-cli-stable-{version}-linux-arm64".to_string()],
@@ -25,6 +26,7 @@ pub fn release() -> Package {
                 }),
                 tag_version_regex_template: Some(r"^stable-(\d+.\d+.\d+)$".to_string()),
                 scan_dirs: None,
+                ..Default::default()
             }),
             PackageTargetType::MacOSAmd64(PackageManagement {
                 artifact_templates: vec!["linkerd2-cli-stable-{version}-darwin".to_string()],
@@ -33,6 +35,7 @@ pub fn release() -> Package {
                 }),
                 tag_version_regex_template: Some(r"^stable-(\d+.\d+.\d+)$".to_string()),
                 scan_dirs: None,
+                ..Default::default()
             }),
             PackageTargetType::WindowsAmd64(PackageManagement {
                 artifact_templates: vec!["linkerd2-cli-stable-{version}-windows.exe".to_string()],
@@ -41,6 +44,7 @@ pub fn release() -> Package {
                 }),
                 tag_version_regex_template: Some(r"^stable-(\d+.\d+.\d+)$".to_string()),
                 scan_dirs: None,
+                ..Default::default()
             }),
         ],
         ..Default::default()
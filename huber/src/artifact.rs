@@ -0,0 +1,304 @@
+use std::collections::HashSet;
+use std::fs::{copy, read_dir, remove_dir_all, remove_file, rename, File};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use chrono::{SecondsFormat, Utc};
+use fs2::FileExt;
+use log::debug;
+use sha2::{Digest, Sha256};
+
+use crate::fs::dir;
+use crate::model::artifact::ArtifactCacheEntry;
+use crate::model::config::{Config, ConfigPath};
+
+/// Content-addressed cache of downloaded artifacts.
+///
+/// Artifacts are stored as `blobs/<sha256>`, and `entries/<sha256 of URL>.yaml` maps the download URL to the artifact.
+/// The cache is shared by huber processes, so it's locked while being modified.
+pub struct ArtifactCache {
+    dir: PathBuf,
+    max_size: u64,
+}
+
+impl ArtifactCache {
+    pub fn new(dir: PathBuf, max_size: u64) -> Self {
+        Self { dir, max_size }
+    }
+
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        Ok(Self::new(
+            config.artifact_cache_dir()?,
+            config.artifact_cache.max_size_mb * 1024 * 1024,
+        ))
+    }
+
+    /// Get the cached artifact of the URL. If the SHA-256 checksum is known, the artifact is looked up
+    /// by the checksum, so the same artifact downloaded from another URL is reused as well
+    pub fn get(&self, url: &str, checksum: Option<&str>) -> anyhow::Result<Option<PathBuf>> {
+        let checksum = match checksum.filter(|it| is_sha256(it)) {
+            Some(checksum) => checksum.to_lowercase(),
+            None => match self.read_entry(url) {
+                Some(entry) => entry.checksum,
+                None => return Ok(None),
+            },
+        };
+
+        let blob = self.blob_path(&checksum)?;
+        if !blob.exists() {
+            return Ok(None);
+        }
+
+        debug!("Found the cached artifact {:?} of {}", blob, url);
+        self.write_entry(&ArtifactCacheEntry {
+            url: url.to_string(),
+            checksum,
+            size: blob.metadata()?.len(),
+            last_used: now(),
+        })?;
+
+        Ok(Some(blob))
+    }
+
+    /// Put the artifact of the URL into the cache, and evict the least recently used artifacts
+    /// if the cache size exceeds the limit
+    pub fn put(
+        &self,
+        url: &str,
+        path: &Path,
+        checksum: &str,
+    ) -> anyhow::Result<ArtifactCacheEntry> {
+        let checksum = checksum.to_lowercase();
+        let blob = self.blob_path(&checksum)?;
+        let _lock = self.lock()?;
+
+        if !blob.exists() {
+            debug!("Caching the artifact {:?} of {} to {:?}", path, url, blob);
+
+            // copy to a temporary file first, so the cache shared by huber processes is never partially written
+            let temp_blob = blob.with_extension(format!("{}.tmp", process::id()));
+            copy(path, &temp_blob)?;
+            rename(&temp_blob, &blob)?;
+        }
+
+        let entry = ArtifactCacheEntry {
+            url: url.to_string(),
+            checksum,
+            size: blob.metadata()?.len(),
+            last_used: now(),
+        };
+        self.write_entry(&entry)?;
+        self.prune_unlocked(self.max_size)?;
+
+        Ok(entry)
+    }
+
+    /// List the cached artifacts, the most recently used first
+    pub fn list(&self) -> anyhow::Result<Vec<ArtifactCacheEntry>> {
+        let mut entries: Vec<ArtifactCacheEntry> = vec![];
+
+        for entry in read_dir(self.entries_dir()?)? {
+            let path = entry?.path();
+            match File::open(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|f| Ok(serde_yaml::from_reader(f)?))
+            {
+                Ok(entry) => entries.push(entry),
+                Err(e) => debug!("Ignored the invalid cache entry {:?}: {}", path, e),
+            }
+        }
+
+        entries.sort_by(|x, y| y.last_used.cmp(&x.last_used));
+        Ok(entries)
+    }
+
+    /// Evict the least recently used artifacts until the cache size is within the limit,
+    /// and remove the entries of missing artifacts and the artifacts not referenced by any entry
+    pub fn prune(&self, max_size: u64) -> anyhow::Result<Vec<ArtifactCacheEntry>> {
+        let _lock = self.lock()?;
+        self.prune_unlocked(max_size)
+    }
+
+    pub fn clear(&self) -> anyhow::Result<()> {
+        debug!("Clearing the artifact cache {:?}", self.dir);
+
+        let _lock = self.lock()?;
+        remove_dir_all(self.blobs_dir()?)?;
+        remove_dir_all(self.entries_dir()?)?;
+
+        Ok(())
+    }
+
+    fn prune_unlocked(&self, max_size: u64) -> anyhow::Result<Vec<ArtifactCacheEntry>> {
+        let mut total_size = 0;
+        let mut kept_checksums: HashSet<String> = HashSet::new();
+        let mut evicted_entries: Vec<ArtifactCacheEntry> = vec![];
+
+        for entry in self.list()? {
+            let blob = self.blob_path(&entry.checksum)?;
+
+            if !blob.exists() {
+                debug!(
+                    "Removing the cache entry of the missing artifact {}",
+                    entry.url
+                );
+                remove_file(self.entry_path(&entry.url)?)?;
+                continue;
+            }
+
+            if kept_checksums.contains(&entry.checksum) {
+                continue;
+            }
+
+            if total_size + entry.size > max_size {
+                debug!("Evicting the cached artifact {}", entry.url);
+                remove_file(self.entry_path(&entry.url)?)?;
+                evicted_entries.push(entry);
+                continue;
+            }
+
+            total_size += entry.size;
+            kept_checksums.insert(entry.checksum);
+        }
+
+        for blob in read_dir(self.blobs_dir()?)? {
+            let path = blob?.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            if !kept_checksums.contains(name.as_ref()) {
+                debug!("Removing the unreferenced cached artifact {:?}", path);
+                remove_file(&path)?;
+            }
+        }
+
+        Ok(evicted_entries)
+    }
+
+    // lock the cache exclusively until the returned file is dropped
+    fn lock(&self) -> anyhow::Result<File> {
+        let file = File::create(dir(self.dir.clone())?.join(".lock"))?;
+        file.lock_exclusive()?;
+
+        Ok(file)
+    }
+
+    fn read_entry(&self, url: &str) -> Option<ArtifactCacheEntry> {
+        let file = File::open(self.entry_path(url).ok()?).ok()?;
+        serde_yaml::from_reader(file).ok()
+    }
+
+    fn write_entry(&self, entry: &ArtifactCacheEntry) -> anyhow::Result<()> {
+        let path = self.entry_path(&entry.url)?;
+        let temp_path = path.with_extension(format!("{}.tmp", process::id()));
+
+        serde_yaml::to_writer(File::create(&temp_path)?, entry)?;
+        rename(&temp_path, &path)?;
+
+        Ok(())
+    }
+
+    fn blobs_dir(&self) -> anyhow::Result<PathBuf> {
+        dir(self.dir.join("blobs"))
+    }
+
+    fn entries_dir(&self) -> anyhow::Result<PathBuf> {
+        dir(self.dir.join("entries"))
+    }
+
+    fn blob_path(&self, checksum: &str) -> anyhow::Result<PathBuf> {
+        Ok(self.blobs_dir()?.join(checksum))
+    }
+
+    fn entry_path(&self, url: &str) -> anyhow::Result<PathBuf> {
+        let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+        Ok(self.entries_dir()?.join(key).with_extension("yaml"))
+    }
+}
+
+fn is_sha256(checksum: &str) -> bool {
+    checksum.len() == 64 && checksum.chars().all(|it| it.is_ascii_hexdigit())
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use super::*;
+    use crate::checksum::sha256_file;
+
+    fn artifact(dir: &Path, name: &str, content: &str) -> (PathBuf, String) {
+        let path = dir.join(name);
+        write(&path, content).unwrap();
+        let checksum = sha256_file(&path).unwrap();
+
+        (path, checksum)
+    }
+
+    #[test]
+    fn test_get_put() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArtifactCache::new(dir.path().join("cache"), 1024);
+        let (path, checksum) = artifact(dir.path(), "pkg.tar.gz", "pkg");
+
+        assert_eq!(
+            cache.get("https://example.com/pkg.tar.gz", None).unwrap(),
+            None
+        );
+
+        cache
+            .put("https://example.com/pkg.tar.gz", &path, &checksum)
+            .unwrap();
+        let cached = cache
+            .get("https://example.com/pkg.tar.gz", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(sha256_file(&cached).unwrap(), checksum);
+
+        // content addressed
+        assert!(cache
+            .get("https://mirror.example.com/pkg.tar.gz", Some(&checksum))
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            cache
+                .get("https://example.com/pkg.tar.gz", Some(&"0".repeat(64)))
+                .unwrap(),
+            None
+        );
+        assert_eq!(cache.list().unwrap().len(), 2);
+
+        cache.clear().unwrap();
+        assert!(cache.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArtifactCache::new(dir.path().join("cache"), 1024);
+        let (path1, checksum1) = artifact(dir.path(), "pkg1", &"1".repeat(600));
+        let (path2, checksum2) = artifact(dir.path(), "pkg2", &"2".repeat(600));
+
+        cache
+            .put("https://example.com/pkg1", &path1, &checksum1)
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        cache
+            .put("https://example.com/pkg2", &path2, &checksum2)
+            .unwrap();
+
+        // the least recently used one is evicted
+        let entries = cache.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].url, "https://example.com/pkg2");
+        assert_eq!(cache.get("https://example.com/pkg1", None).unwrap(), None);
+
+        let evicted = cache.prune(0).unwrap();
+        assert_eq!(evicted.len(), 1);
+        assert!(cache.list().unwrap().is_empty());
+        assert_eq!(read_dir(cache.blobs_dir().unwrap()).unwrap().count(), 0);
+    }
+}
@@ -8,6 +8,7 @@ use std::sync::Arc;
 use anyhow::anyhow;
 use clap::{CommandFactory, Parser, ValueHint};
 use clap_complete::Generator;
+use huber::cmd::cache::CacheCommands;
 use huber::cmd::config::ConfigCommands;
 use huber::cmd::lock::LockCommands;
 use huber::cmd::repo::RepoCommands;
@@ -19,6 +20,7 @@ use huber::log::Logger;
 use huber::model::config::Config;
 use huber::service::config::{ConfigService, DEFAULT_CONFIG};
 use huber::service::init_services;
+use huber::shim::{get_shim_path, run_shim};
 use libcli_rs::output::OutputFormat;
 use log::{debug, error, LevelFilter};
 use scopeguard::defer;
@@ -94,12 +96,41 @@ struct Cli {
     )]
     github_base_uri: Option<String>,
 
+    #[arg(
+        help = "GitLab token; Optional, if you want to access private GitLab projects or reach the rate limit of GitLab API",
+        long,
+        global = true,
+        num_args = 1,
+        value_hint = ValueHint::Unknown,
+        env = "GITLAB_TOKEN",
+    )]
+    gitlab_token: Option<String>,
+
+    #[arg(
+        help = "Offline mode; Resolve and install packages only from the cached package index, cached releases and the mirror directory",
+        long,
+        global = true,
+        env = "HUBER_OFFLINE"
+    )]
+    offline: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[tokio::main]
 async fn main() {
+    // run the executable resolved by the shim linked in the bin directory
+    if let Some(shim_path) = get_shim_path() {
+        match run_shim(&shim_path).await {
+            Ok(code) => exit(code),
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
+        }
+    }
+
     let cli = Cli::parse();
 
     let (config, container) = init(&cli).await;
@@ -136,6 +167,19 @@ async fn main() {
             }
         }
         Commands::Unlock(args) => args.run(&config, &container).await,
+        Commands::Sync(args) => args.run(&config, &container).await,
+        Commands::Cache(args) => match args.command {
+            CacheCommands::List(ref args) => args.run(&config, &container).await,
+            CacheCommands::Prune(ref args) => args.run(&config, &container).await,
+            CacheCommands::Clear(ref args) => args.run(&config, &container).await,
+        },
+        Commands::Mirror(args) => args.run(&config, &container).await,
+        Commands::Outdated(args) => args.run(&config, &container).await,
+        Commands::Rollback(args) => args.run(&config, &container).await,
+        Commands::Doctor(args) => args.run(&config, &container).await,
+        Commands::Env(args) => args.run(&config, &container).await,
+        Commands::Init(args) => args.run(&config, &container).await,
+        Commands::Run(args) => args.run(&config, &container).await,
         Commands::Completions { shell } => {
             shell.generate(&Cli::command(), &mut io::stdout());
             Ok(())
@@ -143,9 +187,14 @@ async fn main() {
     };
 
     if let Err(e) = result {
+        // distinguish outdated packages from failures for CI
+        let code = match e.downcast_ref::<HuberError>() {
+            Some(HuberError::PackagesOutdated(_)) => 2,
+            _ => 1,
+        };
         defer! {
             debug!("{:?}", e);
-            exit(1);
+            exit(code);
         }
 
         if let Some(e) = e.downcast_ref::<HuberError>() {
@@ -163,7 +212,7 @@ async fn main() {
 async fn init(cli: &Cli) -> (Config, Arc<DIContainer>) {
     better_panic::install();
 
-    let config = Config::new(
+    let mut config = Config::new(
         cli.log_level.to_string(),
         OutputFormat::from_str(&cli.output_format).unwrap(),
         dir(PathBuf::from(&cli.huber_dir)).unwrap(),
@@ -172,6 +221,12 @@ async fn init(cli: &Cli) -> (Config, Arc<DIContainer>) {
         cli.github_base_uri.clone(),
         Default::default(),
     );
+    if let Some(token) = cli.gitlab_token.clone() {
+        config.gitlab_token = Some(token);
+    }
+    if cli.offline {
+        config.offline = true;
+    }
 
     Logger::init(&config).expect("Failed to init logger");
 
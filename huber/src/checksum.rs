@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use anyhow::anyhow;
+use regex::Regex;
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::error::HuberError;
+
+const CHECKSUM_FILE_EXTS: [&str; 4] = [".sha256", ".sha512", ".sha256sum", ".sha512sum"];
+
+/// Parse the content of a checksum file into a map of artifact file name to checksum
+///
+/// Supported formats are GNU coreutils (`<checksum>  [*]<file>`), BSD (`SHA256 (<file>) = <checksum>`)
+/// and a single checksum for the artifact named after the checksum file (e.g. `<file>.sha256`).
+pub fn parse_checksums(content: &str, checksum_file_name: &str) -> HashMap<String, String> {
+    let gnu_regex = Regex::new(r"^([0-9a-fA-F]{64}|[0-9a-fA-F]{128})\s+\*?(\S+)$").unwrap();
+    let bsd_regex =
+        Regex::new(r"^SHA(?:256|512) \((.+)\) = ([0-9a-fA-F]{64}|[0-9a-fA-F]{128})$").unwrap();
+    let single_regex = Regex::new(r"^([0-9a-fA-F]{64}|[0-9a-fA-F]{128})$").unwrap();
+
+    let mut checksums = HashMap::new();
+
+    for line in content
+        .lines()
+        .map(|it| it.trim())
+        .filter(|it| !it.is_empty())
+    {
+        if let Some(captures) = gnu_regex.captures(line) {
+            let file_name = file_name(&captures[2]);
+            checksums.insert(file_name, captures[1].to_lowercase());
+        } else if let Some(captures) = bsd_regex.captures(line) {
+            let file_name = file_name(&captures[1]);
+            checksums.insert(file_name, captures[2].to_lowercase());
+        } else if let Some(captures) = single_regex.captures(line) {
+            let file_name = CHECKSUM_FILE_EXTS
+                .iter()
+                .find_map(|ext| checksum_file_name.strip_suffix(ext))
+                .unwrap_or(checksum_file_name);
+            checksums.insert(file_name.to_string(), captures[1].to_lowercase());
+        }
+    }
+
+    checksums
+}
+
+pub fn sha256_file(path: &Path) -> anyhow::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+pub fn sha512_file(path: &Path) -> anyhow::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha512::new();
+    io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Verify the file against the expected checksum, SHA-256 or SHA-512 is decided by the checksum length
+pub fn verify_checksum(path: &Path, artifact: &str, expected: &str) -> anyhow::Result<()> {
+    let expected = expected.to_lowercase();
+    let actual = match expected.len() {
+        64 => sha256_file(path)?,
+        128 => sha512_file(path)?,
+        _ => {
+            return Err(anyhow!(
+                "Unsupported checksum {} of {}, only SHA-256 or SHA-512 supported",
+                expected,
+                artifact
+            ))
+        }
+    };
+
+    if actual != expected {
+        return Err(anyhow!(HuberError::ChecksumMismatch {
+            artifact: artifact.to_string(),
+            expected,
+            actual,
+        }));
+    }
+
+    Ok(())
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    const SHA256_HELLO: &str = "5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03";
+
+    #[test]
+    fn test_parse_checksums() {
+        let content = format!(
+            "{}  pkg_linux_amd64.tar.gz\n{} *dist/pkg_darwin_arm64.tar.gz\n\nSHA256 (pkg.zip) = {}\n",
+            SHA256_HELLO,
+            SHA256_HELLO.to_uppercase(),
+            SHA256_HELLO
+        );
+
+        let checksums = parse_checksums(&content, "checksums.txt");
+        assert_eq!(checksums.len(), 3);
+        assert_eq!(checksums["pkg_linux_amd64.tar.gz"], SHA256_HELLO);
+        assert_eq!(checksums["pkg_darwin_arm64.tar.gz"], SHA256_HELLO);
+        assert_eq!(checksums["pkg.zip"], SHA256_HELLO);
+
+        let checksums = parse_checksums(SHA256_HELLO, "pkg_linux_amd64.sha256");
+        assert_eq!(checksums["pkg_linux_amd64"], SHA256_HELLO);
+
+        let checksums = parse_checksums("not a checksum", "pkg.sha256");
+        assert!(checksums.is_empty());
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pkg");
+        File::create(&path).unwrap().write_all(b"hello\n").unwrap();
+
+        assert!(verify_checksum(&path, "pkg", SHA256_HELLO).is_ok());
+        assert!(verify_checksum(&path, "pkg", &SHA256_HELLO.to_uppercase()).is_ok());
+
+        let err = verify_checksum(&path, "pkg", &"0".repeat(64)).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<HuberError>(),
+            Some(HuberError::ChecksumMismatch { .. })
+        ));
+
+        assert!(verify_checksum(&path, "pkg", &"0".repeat(128)).is_err());
+        assert!(verify_checksum(&path, "pkg", "1234").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use std::io::stdout;
+
+use async_trait::async_trait;
+use clap::{Args, Subcommand};
+use libcli_rs::output;
+use libcli_rs::output::{OutputFactory, OutputTrait};
+use log::info;
+use simpledi_rs::di::DIContainer;
+
+use crate::artifact::ArtifactCache;
+use crate::cmd::CommandTrait;
+use crate::lock_huber_ops;
+use crate::model::config::Config;
+
+#[derive(Args)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub command: CacheCommands,
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    #[command(about = "Show cached artifacts", bin_name = "list")]
+    List(CacheListArgs),
+
+    #[command(
+        about = "Evict the least recently used artifacts exceeding the cache size limit",
+        bin_name = "prune"
+    )]
+    Prune(CachePruneArgs),
+
+    #[command(about = "Remove all cached artifacts", bin_name = "clear")]
+    Clear(CacheClearArgs),
+}
+
+#[derive(Args)]
+pub struct CacheListArgs {}
+
+#[async_trait]
+impl CommandTrait for CacheListArgs {
+    async fn run(&self, config: &Config, _: &DIContainer) -> anyhow::Result<()> {
+        let entries = ArtifactCache::from_config(config)?.list()?;
+        if entries.is_empty() {
+            info!("No cached artifacts");
+            return Ok(());
+        }
+
+        output!(
+            config.output_format,
+            .display(
+                stdout(),
+                &entries,
+                None,
+                None,
+            )
+        )
+    }
+}
+
+#[derive(Args)]
+pub struct CachePruneArgs {
+    #[arg(
+        help = "Maximum cache size in MB [default: artifact_cache.max_size_mb in config]",
+        long,
+        num_args = 1
+    )]
+    max_size: Option<u64>,
+}
+
+#[async_trait]
+impl CommandTrait for CachePruneArgs {
+    async fn run(&self, config: &Config, _: &DIContainer) -> anyhow::Result<()> {
+        lock_huber_ops!(config);
+
+        let max_size_mb = self.max_size.unwrap_or(config.artifact_cache.max_size_mb);
+        let evicted_entries =
+            ArtifactCache::from_config(config)?.prune(max_size_mb * 1024 * 1024)?;
+
+        for entry in &evicted_entries {
+            info!("Evicted the cached artifact {}", entry.url);
+        }
+        info!("{} cached artifacts evicted", evicted_entries.len());
+
+        Ok(())
+    }
+}
+
+#[derive(Args)]
+pub struct CacheClearArgs {}
+
+#[async_trait]
+impl CommandTrait for CacheClearArgs {
+    async fn run(&self, config: &Config, _: &DIContainer) -> anyhow::Result<()> {
+        lock_huber_ops!(config);
+
+        info!("Clearing the artifact cache");
+        ArtifactCache::from_config(config)?.clear()?;
+        info!("Artifact cache cleared");
+
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ use crate::error::HuberError::PackageNotInstalled;
 use crate::lock_huber_ops;
 use crate::model::config::Config;
 use crate::parse::parse_pkg_name_optional_semver;
+use crate::semver::{find_max_satisfied_version, parse_version_req};
 use crate::service::package::PackageService;
 use crate::service::release::{ReleaseAsyncTrait, ReleaseService};
 use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait};
@@ -17,7 +18,7 @@ use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait};
 #[derive(Args)]
 pub struct CurrentArgs {
     #[arg(
-        help = "Package name with version (e.g. 'package-name@version')",
+        help = "Package name with version or version requirement (e.g. 'package-name@version', 'package-name@^0.32')",
         num_args = 1,
         value_hint = ValueHint::Unknown,
         value_parser = parse_pkg_name_optional_semver,
@@ -42,6 +43,25 @@ impl CommandTrait for CurrentArgs {
             let pkg = pkg_service.get(name)?;
             let releases = release_service.find(&pkg).await?;
 
+            if let Some(requirement) = parse_version_req(version) {
+                let installed_versions = releases.iter().map(|r| r.version.clone());
+                let Some(version) = find_max_satisfied_version(&requirement, installed_versions)
+                else {
+                    anyhow::bail!(
+                        "No installed version matching '{}' found for {}",
+                        requirement,
+                        name
+                    );
+                };
+
+                let mut r = releases.into_iter().find(|r| r.version == version).unwrap();
+                info!("Updating the current version of {} to {}", name, version);
+                release_service.set_current(&mut r).await?;
+                info!("{}@{} is now the current version", name, version);
+
+                continue;
+            }
+
             if Version::parse(version.trim_start_matches('v')).is_err() {
                 if let Some(mut r) = releases.clone().into_iter().find(|r| r.version.eq(version)) {
                     info!("Updating the current version of {} to {}", name, version);
@@ -0,0 +1,304 @@
+use std::env;
+use std::fs::{read_dir, remove_dir_all, remove_file, File};
+use std::io::stdout;
+use std::path::Path;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::Args;
+use libcli_rs::output;
+use libcli_rs::output::{OutputFactory, OutputTrait};
+use log::{debug, info};
+use simpledi_rs::di::{DIContainer, DIContainerTrait};
+use symlink::remove_symlink_file;
+
+use crate::cmd::CommandTrait;
+use crate::error::HuberError::DoctorProblemsFound;
+use crate::lock_huber_ops;
+use crate::model::config::{Config, ConfigPath};
+use crate::model::doctor::{DoctorProblem, DoctorProblemKind};
+use crate::model::package::Package;
+use crate::model::release::{Release, ReleaseIndex, SortModelTrait};
+use crate::service::package::PackageService;
+use crate::service::release::{ReleaseService, ReleaseTrait};
+use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait};
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    #[arg(help = "Repair the problems found", long, num_args = 0)]
+    fix: bool,
+}
+
+#[async_trait]
+impl CommandTrait for DoctorArgs {
+    async fn run(&self, config: &Config, container: &DIContainer) -> anyhow::Result<()> {
+        lock_huber_ops!(config);
+
+        let release_service = container.get::<ReleaseService>().unwrap();
+        let pkg_service = container.get::<PackageService>().unwrap();
+
+        let mut problems = vec![];
+        let indexes = read_current_indexes(config)?;
+        for index in indexes.iter() {
+            problems.extend(
+                check_package(config, release_service, pkg_service, index, self.fix).await?,
+            );
+        }
+        // packages installed only by `huber run` have installed versions but no current release
+        for pkg in find_non_current_packages(config, pkg_service, &indexes)? {
+            problems.extend(check_version_dirs(config, &pkg, self.fix)?);
+        }
+        // checked after the packages, because fixing them may leave dangling executable links
+        problems.extend(check_executables(config, self.fix)?);
+        problems.extend(check_path(config)?);
+
+        if problems.is_empty() {
+            info!("No problems found");
+            return Ok(());
+        }
+
+        output!(config.output_format, .display(
+            stdout(),
+            &problems,
+            None,
+            None,
+        ))?;
+
+        let count = problems.iter().filter(|it| !it.fixed).count();
+        if count > 0 {
+            return Err(anyhow!(DoctorProblemsFound(count)));
+        }
+
+        Ok(())
+    }
+}
+
+async fn check_package(
+    config: &Config,
+    release_service: &ReleaseService,
+    pkg_service: &PackageService,
+    index: &ReleaseIndex,
+    fix: bool,
+) -> anyhow::Result<Vec<DoctorProblem>> {
+    debug!("Checking the installed package {}", index.name);
+
+    if !pkg_service.has(&index.name)? {
+        let mut problem = DoctorProblem::new(
+            DoctorProblemKind::PackageNotFound,
+            &index.name,
+            "Installed package not found in any repository",
+        );
+        if fix {
+            remove_unknown_package(config, index)?;
+            problem.fixed = true;
+        }
+
+        return Ok(vec![problem]);
+    }
+
+    let pkg = pkg_service.get(&index.name)?;
+    let mut problems = check_version_dirs(config, &pkg, fix)?;
+
+    if let Err(e) = release_service.current(&pkg) {
+        let mut problem = DoctorProblem::new(
+            DoctorProblemKind::InvalidCurrentRelease,
+            &pkg.name,
+            &format!(
+                "Current release {}@{} not readable: {}",
+                pkg.name, index.version, e
+            ),
+        );
+        if fix {
+            // switch to the latest installed version, or remove the package from the current index
+            let mut releases = release_service.find(&pkg).await?;
+            releases.sort_by_version();
+
+            match releases.first() {
+                Some(release) => {
+                    info!("Restoring {} as the current release", release);
+                    release_service.restore_current(release).await?;
+                }
+                None => {
+                    info!("Removing {} from the current index", pkg.name);
+                    release_service.reset_current(&pkg)?;
+                }
+            }
+            problem.fixed = true;
+        }
+
+        problems.push(problem);
+    }
+
+    Ok(problems)
+}
+
+// version directories without manifests, usually left by failed installations
+fn check_version_dirs(
+    config: &Config,
+    pkg: &Package,
+    fix: bool,
+) -> anyhow::Result<Vec<DoctorProblem>> {
+    let mut problems = vec![];
+
+    for entry in read_dir(config.installed_pkg_base_dir(pkg)?)? {
+        let path = entry?.path();
+        let filename = path.file_name().unwrap().to_string_lossy().to_string();
+        if filename == "current" || !path.is_dir() {
+            continue;
+        }
+
+        let release = config
+            .installed_pkg_manifest_file(pkg, &filename)
+            .and_then(|it| read_release(&it));
+        if let Err(e) = release {
+            let mut problem = DoctorProblem::new(
+                DoctorProblemKind::OrphanVersionDir,
+                &path.to_string_lossy(),
+                &format!(
+                    "Release manifest of {}@{} not readable: {}",
+                    pkg.name, filename, e
+                ),
+            );
+            if fix {
+                remove_dir_all(&path)?;
+                problem.fixed = true;
+            }
+
+            problems.push(problem);
+        }
+    }
+
+    Ok(problems)
+}
+
+// find the known packages in the installed package directory, which are not in the current index
+fn find_non_current_packages(
+    config: &Config,
+    pkg_service: &PackageService,
+    indexes: &[ReleaseIndex],
+) -> anyhow::Result<Vec<Package>> {
+    let mut pkgs = vec![];
+
+    for source_entry in read_dir(config.installed_pkg_root_dir()?)? {
+        let source_dir = source_entry?.path();
+        if !source_dir.is_dir() {
+            continue;
+        }
+        let source = source_dir
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .to_string();
+
+        for entry in read_dir(&source_dir)? {
+            let dirname = entry?.file_name().to_string_lossy().to_string();
+            if indexes
+                .iter()
+                .any(|it| it.source == source && format!("{}_{}", it.owner, it.name) == dirname)
+            {
+                continue;
+            }
+
+            // the directory is named by the owner and the name, which may both contain underscores
+            let pkg = dirname
+                .match_indices('_')
+                .map(|(i, _)| (&dirname[..i], &dirname[i + 1..]))
+                .filter_map(|(owner, name)| pkg_service.get(name).ok().map(|it| (owner, it)))
+                .find(|(owner, pkg)| {
+                    pkg.source.to_string() == source && pkg.source.owner() == *owner
+                })
+                .map(|(_, pkg)| pkg);
+            match pkg {
+                Some(pkg) => pkgs.push(pkg),
+                None => debug!("Ignored unknown package directory {:?}", dirname),
+            }
+        }
+    }
+
+    Ok(pkgs)
+}
+
+fn check_executables(config: &Config, fix: bool) -> anyhow::Result<Vec<DoctorProblem>> {
+    let mut problems = vec![];
+
+    for entry in read_dir(config.bin_dir()?)? {
+        let path = entry?.path();
+        if !path.is_symlink() || path.exists() {
+            continue;
+        }
+
+        let target = path.read_link()?;
+        let mut problem = DoctorProblem::new(
+            DoctorProblemKind::DanglingExecutable,
+            &path.to_string_lossy(),
+            &format!("Linked executable {:?} not found", target),
+        );
+        if fix {
+            remove_symlink_file(&path)?;
+            problem.fixed = true;
+        }
+
+        problems.push(problem);
+    }
+
+    Ok(problems)
+}
+
+fn check_path(config: &Config) -> anyhow::Result<Vec<DoctorProblem>> {
+    let bin_dir = config.bin_dir()?;
+    let canonical_bin_dir = bin_dir.canonicalize().unwrap_or(bin_dir.clone());
+
+    let found = env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths)
+            .any(|it| it == bin_dir || it.canonicalize().is_ok_and(|it| it == canonical_bin_dir))
+    });
+    if found {
+        return Ok(vec![]);
+    }
+
+    // not fixable, because the shell profile is managed by users
+    Ok(vec![DoctorProblem::new(
+        DoctorProblemKind::BinDirNotInPath,
+        &bin_dir.to_string_lossy(),
+        "Add the bin directory to the PATH environment variable",
+    )])
+}
+
+fn read_current_indexes(config: &Config) -> anyhow::Result<Vec<ReleaseIndex>> {
+    let index_f = config.current_index_file()?;
+    if !index_f.exists() {
+        return Ok(vec![]);
+    }
+
+    Ok(serde_yaml::from_reader(File::open(index_f)?)?)
+}
+
+fn read_release(path: &Path) -> anyhow::Result<Release> {
+    Ok(serde_yaml::from_reader(File::open(path)?)?)
+}
+
+// remove the installed directory and the current index entry of the package, which is unknown to repositories
+fn remove_unknown_package(config: &Config, index: &ReleaseIndex) -> anyhow::Result<()> {
+    let pkg_base_dir = config
+        .installed_pkg_root_dir()?
+        .join(&index.source)
+        .join(format!("{}_{}", index.owner, index.name));
+    if pkg_base_dir.exists() {
+        info!("Removing {:?}", pkg_base_dir);
+        remove_dir_all(&pkg_base_dir)?;
+    }
+
+    info!("Removing {} from the current index", index.name);
+    let indexes: Vec<_> = read_current_indexes(config)?
+        .into_iter()
+        .filter(|it| it.name != index.name)
+        .collect();
+
+    let index_f = config.current_index_file()?;
+    let _ = remove_file(&index_f);
+    if !indexes.is_empty() {
+        serde_yaml::to_writer(File::create(&index_f)?, &indexes)?;
+    }
+
+    Ok(())
+}
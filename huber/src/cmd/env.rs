@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::io::{stdout, Write};
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use clap::{Args, ValueEnum};
+use log::warn;
+use serde_json::json;
+use simpledi_rs::di::{DIContainer, DIContainerTrait};
+
+use crate::cmd::CommandTrait;
+use crate::model::config::{Config, ConfigPath};
+use crate::service::release::ReleaseService;
+use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait};
+use crate::shim::{find_project_release, find_project_version};
+
+// directories added to PATH by the last evaluation, replaced by the next one
+pub const HUBER_ENV_PATH: &str = "HUBER_ENV_PATH";
+// names of the package environment variables exported by the last evaluation
+pub const HUBER_ENV_VARS: &str = "HUBER_ENV_VARS";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, ValueEnum)]
+pub enum ShellKind {
+    Bash,
+    Zsh,
+    Fish,
+    #[value(alias = "nushell")]
+    Nu,
+    Sh,
+}
+
+#[derive(Args)]
+pub struct EnvArgs {
+    #[arg(
+        help = "Shell to print the environment variables for",
+        long,
+        num_args = 1,
+        value_enum,
+        default_value = "sh"
+    )]
+    shell: ShellKind,
+
+    #[arg(
+        help = "Print the environment variables as a JSON object, where the unset ones are null (e.g. for direnv)",
+        long,
+        num_args = 0
+    )]
+    json: bool,
+}
+
+#[async_trait]
+impl CommandTrait for EnvArgs {
+    async fn run(&self, config: &Config, container: &DIContainer) -> anyhow::Result<()> {
+        let release_service = container.get::<ReleaseService>().unwrap();
+
+        let vars = get_env_vars(config, release_service, &env::current_dir()?).await?;
+        let output = if self.json {
+            serde_json::to_string_pretty(&vars)?
+        } else {
+            format_env_vars(self.shell, &vars)?
+        };
+
+        writeln!(stdout(), "{}", output)?;
+        Ok(())
+    }
+}
+
+/// Get the environment variables activating the bin directory, the installed project versions required by
+/// the nearest project version files of the directory, and the environment declared by the activated packages.
+/// The variables to unset are None
+pub async fn get_env_vars(
+    config: &Config,
+    release_service: &ReleaseService,
+    dir: &Path,
+) -> anyhow::Result<BTreeMap<String, Option<String>>> {
+    let mut path_dirs = vec![];
+    let mut pkg_vars = BTreeMap::new();
+
+    for release in release_service.list()? {
+        let pkg = &release.package;
+        let mut activated_release = release.clone();
+
+        if let Some(project_version) = find_project_version(dir, &pkg.name)? {
+            match find_project_release(pkg, &project_version, release_service.find(pkg).await?) {
+                Ok(Some(project_release)) if project_release.version != release.version => {
+                    path_dirs.extend(
+                        release_service.get_executable_dirs(
+                            &project_release.package,
+                            &project_release.version,
+                        )?,
+                    );
+                    activated_release = project_release;
+                }
+                Ok(_) => {}
+                Err(e) => warn!("{}", e),
+            }
+        }
+
+        if let Some(release_env) = release_service.get_release_env(&activated_release)? {
+            path_dirs.extend(release_env.path_dirs);
+            pkg_vars.extend(release_env.vars);
+        }
+    }
+    path_dirs.push(config.bin_dir()?);
+
+    let mut vars = export_vars(pkg_vars, env::var(HUBER_ENV_VARS).ok());
+    let (path, activated_path) =
+        activate_path_dirs(&path_dirs, env::var_os("PATH"), env::var_os(HUBER_ENV_PATH))?;
+    vars.insert("PATH".to_string(), Some(path));
+    vars.insert(HUBER_ENV_PATH.to_string(), Some(activated_path));
+
+    Ok(vars)
+}
+
+// export the variables, and unset the ones exported previously but not anymore, e.g. after leaving a project
+fn export_vars(
+    vars: BTreeMap<String, String>,
+    exported_vars: Option<String>,
+) -> BTreeMap<String, Option<String>> {
+    let names: Vec<_> = vars.keys().cloned().collect();
+    let mut results: BTreeMap<_, _> = vars.into_iter().map(|(k, v)| (k, Some(v))).collect();
+
+    if let Some(exported_vars) = &exported_vars {
+        for name in exported_vars.split(':').filter(|it| !it.is_empty()) {
+            results.entry(name.to_string()).or_insert(None);
+        }
+    }
+
+    if !names.is_empty() {
+        results.insert(HUBER_ENV_VARS.to_string(), Some(names.join(":")));
+    } else if exported_vars.is_some() {
+        results.insert(HUBER_ENV_VARS.to_string(), None);
+    }
+
+    results
+}
+
+// prepend the directories to PATH, replacing the ones activated previously, so PATH doesn't grow when re-evaluated
+fn activate_path_dirs(
+    path_dirs: &[PathBuf],
+    path: Option<OsString>,
+    activated_path: Option<OsString>,
+) -> anyhow::Result<(String, String)> {
+    let activated_dirs: Vec<_> = activated_path
+        .map(|it| env::split_paths(&it).collect())
+        .unwrap_or_default();
+    let dirs: Vec<_> = path
+        .map(|it| env::split_paths(&it).collect())
+        .unwrap_or_default();
+
+    let path = env::join_paths(
+        path_dirs.iter().cloned().chain(
+            dirs.into_iter()
+                .filter(|it| !activated_dirs.contains(it) && !path_dirs.contains(it)),
+        ),
+    )?;
+    let activated_path = env::join_paths(path_dirs)?;
+
+    Ok((
+        path.to_string_lossy().to_string(),
+        activated_path.to_string_lossy().to_string(),
+    ))
+}
+
+/// Format the environment variables as the script evaluated by the shell
+pub fn format_env_vars(
+    shell: ShellKind,
+    vars: &BTreeMap<String, Option<String>>,
+) -> anyhow::Result<String> {
+    // nushell can't evaluate scripts, so the variables are printed as a record for load-env
+    if shell == ShellKind::Nu {
+        let record: BTreeMap<_, _> = vars
+            .iter()
+            .filter_map(|(name, value)| {
+                value.as_ref().map(|value| {
+                    let value = if name == "PATH" {
+                        json!(env::split_paths(value)
+                            .map(|it| it.to_string_lossy().to_string())
+                            .collect::<Vec<_>>())
+                    } else {
+                        json!(value)
+                    };
+                    (name, value)
+                })
+            })
+            .collect();
+
+        return Ok(serde_json::to_string(&record)?);
+    }
+
+    let lines: Vec<_> = vars
+        .iter()
+        .map(|(name, value)| match (shell, value) {
+            (ShellKind::Fish, Some(value)) if name == "PATH" => format!(
+                "set -gx PATH {}",
+                env::split_paths(value)
+                    .map(|it| quote_fish(&it.to_string_lossy()))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            (ShellKind::Fish, Some(value)) => format!("set -gx {} {}", name, quote_fish(value)),
+            (ShellKind::Fish, None) => format!("set -e {}", name),
+            (_, Some(value)) => format!("export {}={}", name, quote_posix(value)),
+            (_, None) => format!("unset {}", name),
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+pub fn quote_posix(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+pub fn quote_fish(value: &str) -> String {
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_activate_path_dirs() {
+        let path_dirs = vec![PathBuf::from("/project/bin"), PathBuf::from("/huber/bin")];
+
+        let (path, activated_path) = activate_path_dirs(
+            &path_dirs,
+            Some(OsString::from("/old/bin:/huber/bin:/usr/bin")),
+            Some(OsString::from("/old/bin:/huber/bin")),
+        )
+        .unwrap();
+        assert_eq!(path, "/project/bin:/huber/bin:/usr/bin");
+        assert_eq!(activated_path, "/project/bin:/huber/bin");
+
+        // leaving the project
+        let (path, activated_path) = activate_path_dirs(
+            &path_dirs[1..],
+            Some(OsString::from(path)),
+            Some(OsString::from(activated_path)),
+        )
+        .unwrap();
+        assert_eq!(path, "/huber/bin:/usr/bin");
+        assert_eq!(activated_path, "/huber/bin");
+    }
+
+    #[test]
+    fn test_export_vars() {
+        let vars = BTreeMap::from([("GOROOT".to_string(), "/go".to_string())]);
+
+        let results = export_vars(vars.clone(), Some("JAVA_HOME:GOROOT".to_string()));
+        assert_eq!(
+            results,
+            BTreeMap::from([
+                ("GOROOT".to_string(), Some("/go".to_string())),
+                (HUBER_ENV_VARS.to_string(), Some("GOROOT".to_string())),
+                ("JAVA_HOME".to_string(), None),
+            ])
+        );
+
+        let results = export_vars(BTreeMap::new(), Some("GOROOT".to_string()));
+        assert_eq!(
+            results,
+            BTreeMap::from([
+                ("GOROOT".to_string(), None),
+                (HUBER_ENV_VARS.to_string(), None),
+            ])
+        );
+
+        assert!(export_vars(BTreeMap::new(), None).is_empty());
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_format_env_vars() {
+        let vars = BTreeMap::from([
+            ("NAME".to_string(), Some("it's".to_string())),
+            ("PATH".to_string(), Some("/huber/bin:/usr/bin".to_string())),
+            ("UNSET".to_string(), None),
+        ]);
+
+        assert_eq!(
+            format_env_vars(ShellKind::Bash, &vars).unwrap(),
+            "export NAME='it'\\''s'\nexport PATH='/huber/bin:/usr/bin'\nunset UNSET"
+        );
+        assert_eq!(
+            format_env_vars(ShellKind::Fish, &vars).unwrap(),
+            "set -gx NAME 'it\\'s'\nset -gx PATH '/huber/bin' '/usr/bin'\nset -e UNSET"
+        );
+        assert_eq!(
+            format_env_vars(ShellKind::Nu, &vars).unwrap(),
+            r#"{"NAME":"it's","PATH":["/huber/bin","/usr/bin"]}"#
+        );
+    }
+}
@@ -39,6 +39,11 @@ impl CommandTrait for FlushArgs {
                     flushed_releases.push(r);
                 }
             }
+
+            // only the versioned executables of the current release remain linked
+            release_service
+                .link_versioned_executables(&cr.package)
+                .await?;
         }
 
         if flushed_releases.is_empty() {
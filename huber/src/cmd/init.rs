@@ -0,0 +1,120 @@
+use std::env;
+use std::io::{stdout, Write};
+use std::path::Path;
+
+use async_trait::async_trait;
+use clap::Args;
+use simpledi_rs::di::DIContainer;
+
+use crate::cmd::env::{quote_fish, quote_posix, ShellKind};
+use crate::cmd::CommandTrait;
+use crate::model::config::Config;
+
+// bash has no cd hook, so the environment is evaluated in the prompt command when the directory is changed
+const BASH_INIT_SCRIPT: &str = r#"_huber_hook() {
+  local previous_exit_status=$?
+  if [[ "${_HUBER_PWD:-}" != "$PWD" ]]; then
+    _HUBER_PWD="$PWD"
+    eval "$({huber} env --shell bash)"
+  fi
+  return $previous_exit_status
+}
+if [[ ";${PROMPT_COMMAND:-};" != *";_huber_hook;"* ]]; then
+  PROMPT_COMMAND="_huber_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+fi
+"#;
+
+const ZSH_INIT_SCRIPT: &str = r#"_huber_hook() {
+  eval "$({huber} env --shell zsh)"
+}
+typeset -ag chpwd_functions
+if (( ! ${chpwd_functions[(I)_huber_hook]} )); then
+  chpwd_functions=(_huber_hook $chpwd_functions)
+fi
+_huber_hook
+"#;
+
+const FISH_INIT_SCRIPT: &str = r#"function _huber_hook --on-variable PWD
+    {huber} env --shell fish | source
+end
+_huber_hook
+"#;
+
+const NU_INIT_SCRIPT: &str = r#"$env.config = ($env.config | upsert hooks.env_change.PWD {|config|
+    ($config.hooks?.env_change?.PWD? | default []) | append {|before, after|
+        ^{huber} env --shell nu | from json | load-env
+    }
+})
+"#;
+
+// POSIX sh has no hooks, so cd is wrapped
+const SH_INIT_SCRIPT: &str = r#"_huber_hook() {
+  eval "$({huber} env --shell sh)"
+}
+cd() {
+  command cd "$@" && _huber_hook
+}
+_huber_hook
+"#;
+
+#[derive(Args)]
+pub struct InitArgs {
+    #[arg(help = "Shell name", num_args = 1, value_enum)]
+    shell: ShellKind,
+}
+
+#[async_trait]
+impl CommandTrait for InitArgs {
+    async fn run(&self, config: &Config, _container: &DIContainer) -> anyhow::Result<()> {
+        let huber = env::current_exe()?;
+
+        write!(
+            stdout(),
+            "{}",
+            get_init_script(self.shell, &huber, &config.huber_dir)
+        )?;
+        Ok(())
+    }
+}
+
+/// Get the script activating huber in the shell, which evaluates `huber env` when the directory is changed
+pub fn get_init_script(shell: ShellKind, huber: &Path, huber_dir: &Path) -> String {
+    let (script, quote): (_, fn(&str) -> String) = match shell {
+        ShellKind::Bash => (BASH_INIT_SCRIPT, quote_posix),
+        ShellKind::Zsh => (ZSH_INIT_SCRIPT, quote_posix),
+        ShellKind::Fish => (FISH_INIT_SCRIPT, quote_fish),
+        // raw strings of nushell can't be escaped
+        ShellKind::Nu => (NU_INIT_SCRIPT, |it| format!("'{}'", it)),
+        ShellKind::Sh => (SH_INIT_SCRIPT, quote_posix),
+    };
+
+    script.replace(
+        "{huber}",
+        &format!(
+            "{} --huber-dir {}",
+            quote(&huber.to_string_lossy()),
+            quote(&huber_dir.to_string_lossy())
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_init_script() {
+        let huber = Path::new("/opt/huber's/huber");
+        let huber_dir = Path::new("/home/user/.huber");
+
+        let script = get_init_script(ShellKind::Zsh, huber, huber_dir);
+        assert!(script.contains(
+            r#"eval "$('/opt/huber'\''s/huber' --huber-dir '/home/user/.huber' env --shell zsh)""#
+        ));
+
+        let script = get_init_script(ShellKind::Fish, huber, huber_dir);
+        assert!(script.contains(
+            r#"'/opt/huber\'s/huber' --huber-dir '/home/user/.huber' env --shell fish | source"#
+        ));
+    }
+}
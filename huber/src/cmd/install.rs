@@ -1,3 +1,4 @@
+use std::str::FromStr;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -8,23 +9,26 @@ use tokio::task::JoinHandle;
 
 use crate::cmd::get_default_stdlib;
 use crate::cmd::update::is_pkg_locked_for_release;
-use crate::cmd::{get_updated_package_version, CommandTrait, PlatformStdLib};
+use crate::cmd::{
+    get_satisfied_package_version, get_updated_package_version, CommandTrait, PlatformStdLib,
+};
 use crate::lock_huber_ops;
 use crate::model::config::Config;
 use crate::model::package::{default_targets, Package, PackageSource};
-use crate::model::release::Release;
+use crate::model::release::{Release, ReleaseChannel};
 use crate::model::repo::LOCAL_REPO;
 use crate::parse::parse_pkg_name_optional_semver;
+use crate::semver::parse_version_req;
 use crate::service::cache::{CacheAsyncTrait, CacheService};
 use crate::service::package::PackageService;
-use crate::service::release::ReleaseService;
+use crate::service::release::{ReleaseService, ReleaseTrait};
 use crate::service::repo::{RepoAsyncTrait, RepoService};
 use crate::service::{ItemOperationTrait, ItemSearchTrait};
 
 #[derive(Args)]
 pub struct InstallArgs {
     #[arg(
-        help = "Package name (e.g. 'package-name', 'package-name@version')g. 'owner/repo', 'owner/repo@version') for unmanaged packages by repositories",
+        help = "Package name (e.g. 'package-name', 'package-name@version', 'package-name@~1.29'); or 'owner/repo', 'owner/repo@version' for packages not managed by repositories",
         num_args = 1,
         required = true,
         value_parser = parse_pkg_name_optional_semver,
@@ -52,6 +56,15 @@ pub struct InstallArgs {
         value_enum
     )]
     prefer_stdlib: PlatformStdLib,
+
+    #[arg(
+        help = "Release channel to resolve the latest version: 'stable', 'prerelease' or a regex of release tags (e.g. 'nightly-.*'); Default to the channel of the installed package",
+        long,
+        num_args = 1,
+        value_parser = ReleaseChannel::from_str,
+        value_hint = ValueHint::Unknown,
+    )]
+    channel: Option<ReleaseChannel>,
 }
 
 #[async_trait]
@@ -76,6 +89,7 @@ impl CommandTrait for InstallArgs {
             pkg_service,
             &self.name_version,
             self.prefer_stdlib,
+            self.channel.clone(),
         )
         .await?;
 
@@ -139,6 +153,7 @@ pub async fn install_packages(
     pkg_service: Arc<PackageService>,
     pkg_versions: &[(String, String)],
     prefer_stdlib: PlatformStdLib,
+    channel: Option<ReleaseChannel>,
 ) -> anyhow::Result<()> {
     let mut join_handles: Vec<JoinHandle<anyhow::Result<()>>> = vec![];
 
@@ -147,6 +162,7 @@ pub async fn install_packages(
         let pkg_service = pkg_service.clone();
         let release_service = release_service.clone();
         let config = config.clone();
+        let channel = channel.clone();
 
         let handle: JoinHandle<anyhow::Result<()>> = tokio::spawn(async move {
             if !pkg_service.has(&pkg)? {
@@ -155,11 +171,24 @@ pub async fn install_packages(
             }
 
             let mut pkg = pkg_service.get(&pkg)?;
+            // keep following the channel of the installed package if not specified
+            pkg.channel = match channel {
+                Some(channel) => Some(channel),
+                None => release_service
+                    .current(&pkg)
+                    .ok()
+                    .and_then(|it| it.package.channel),
+            };
             let latest_version = release_service
                 .get_latest(&pkg)
                 .await
                 .map(|r| r.version)
                 .or_else(|err| {
+                    // no release is resolvable at all in offline mode
+                    if config.offline {
+                        return Err(err);
+                    }
+
                     warn!(
                         "Failed to get the latest release version of {}: {}",
                         pkg.name, err
@@ -167,7 +196,20 @@ pub async fn install_packages(
                     anyhow::Ok("".to_string())
                 })?;
             let release_check = !latest_version.is_empty();
-            let (version, is_latest) = get_version_to_install(&version, &pkg, &latest_version)?;
+            let (version, is_latest) = match parse_version_req(&version) {
+                Some(requirement) => {
+                    let version =
+                        get_satisfied_package_version(&pkg_service, &pkg.name, &requirement)
+                            .await?;
+                    info!(
+                        "Resolved {}@{} to version {}",
+                        pkg.name, requirement, version
+                    );
+
+                    (version, false)
+                }
+                None => get_version_to_install(&version, &pkg, &latest_version)?,
+            };
 
             if is_pkg_locked_for_release(&config, &pkg, &version) {
                 warn!(
@@ -195,7 +237,7 @@ pub async fn install_packages(
             info!("Installing package {}", msg);
             pkg.version = Some(version.clone());
             release_service
-                .update(&pkg, &prefer_stdlib, release_check)
+                .update(&pkg, &prefer_stdlib, release_check, true)
                 .await?;
             info!("{} installed", msg);
 
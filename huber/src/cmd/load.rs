@@ -53,6 +53,7 @@ impl CommandTrait for LoadArgs {
             pkg_service,
             &versions,
             PlatformStdLib::None,
+            None,
         )
         .await?;
 
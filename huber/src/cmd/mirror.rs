@@ -0,0 +1,210 @@
+use std::env;
+use std::fs::File;
+use std::path::PathBuf;
+use std::slice;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::{Args, ValueHint};
+use log::info;
+use simpledi_rs::di::{DIContainer, DIContainerTrait};
+
+use crate::cmd::sync::resolve_version;
+use crate::cmd::{get_satisfied_package_version, get_updated_package_version, CommandTrait};
+use crate::compress::compress_tar_gz;
+use crate::error::HuberError::{OfflineUnsupported, PackageNotFound};
+use crate::fs::dir;
+use crate::mirror::Mirror;
+use crate::model::config::Config;
+use crate::model::package::Package;
+use crate::model::project::ProjectManifest;
+use crate::parse::parse_pkg_name_optional_semver;
+use crate::semver::parse_version_req;
+use crate::service::cache::{CacheAsyncTrait, CacheService};
+use crate::service::package::PackageService;
+use crate::service::release::ReleaseService;
+use crate::service::ItemOperationTrait;
+
+pub const MIRROR_PKGS_FILE: &str = "huber.yaml";
+
+#[derive(Args)]
+pub struct MirrorArgs {
+    #[arg(
+        help = "Package name (e.g. 'package-name', 'package-name@version', 'package-name@~1.29')",
+        num_args = 1,
+        required_unless_present = "file",
+        value_parser = parse_pkg_name_optional_semver,
+        value_hint = ValueHint::Unknown,
+    )]
+    name_version: Vec<(String, String)>,
+
+    #[arg(
+        help = "Project manifest file listing the packages to mirror",
+        long,
+        num_args = 1,
+        value_hint = ValueHint::FilePath
+    )]
+    file: Option<String>,
+
+    #[arg(
+        help = "Target platform in the format of '<os>/<arch>' (e.g. 'linux/amd64', 'macos/aarch64'); Default to the current platform",
+        long,
+        num_args = 1,
+        value_parser = parse_platform,
+        value_hint = ValueHint::Unknown,
+    )]
+    platform: Vec<(String, String)>,
+
+    #[arg(
+        help = "Output directory of the mirror bundle",
+        long,
+        num_args = 1,
+        default_value = "huber-mirror",
+        value_hint = ValueHint::DirPath
+    )]
+    output: String,
+
+    #[arg(
+        help = "Compress the mirror bundle into a tar.gz archive",
+        long,
+        num_args = 0
+    )]
+    archive: bool,
+}
+
+#[async_trait]
+impl CommandTrait for MirrorArgs {
+    async fn run(&self, config: &Config, container: &DIContainer) -> anyhow::Result<()> {
+        if config.offline {
+            return Err(anyhow!(OfflineUnsupported(
+                "mirroring packages".to_string()
+            )));
+        }
+
+        let release_service = container.get::<ReleaseService>().unwrap();
+        let pkg_service = container.get::<PackageService>().unwrap();
+        let cache_service = container.get::<CacheService>().unwrap();
+
+        cache_service.update_repositories().await?;
+
+        let mut name_versions = self.name_version.clone();
+        if let Some(file) = &self.file {
+            info!("Loading the project manifest {:?}", file);
+            for (name, requirement) in ProjectManifest::load(&PathBuf::from(file))?.packages {
+                if !pkg_service.has(&name)? {
+                    return Err(anyhow!(PackageNotFound(name)));
+                }
+
+                let pkg = pkg_service.get(&name)?;
+                let version =
+                    resolve_version(pkg_service, release_service, &pkg, &requirement).await?;
+                name_versions.push((name, version));
+            }
+        }
+
+        let platforms = if self.platform.is_empty() {
+            vec![(env::consts::OS.to_string(), env::consts::ARCH.to_string())]
+        } else {
+            self.platform.clone()
+        };
+
+        let output_dir = dir(PathBuf::from(&self.output))?;
+        let mirror = Mirror::new(output_dir.clone());
+        let mut pkgs: Vec<Package> = vec![];
+
+        for (name, version) in name_versions {
+            if !pkg_service.has(&name)? {
+                return Err(anyhow!(PackageNotFound(name)));
+            }
+
+            let mut pkg = pkg_service.get(&name)?;
+            if let Some(requirement) = parse_version_req(&version) {
+                pkg.version =
+                    Some(get_satisfied_package_version(pkg_service, &name, &requirement).await?);
+            } else if !version.is_empty() {
+                let latest_release = release_service.get_latest(&pkg).await?;
+                pkg.version = Some(get_updated_package_version(
+                    &version,
+                    &latest_release.version,
+                ));
+            }
+
+            let release = release_service.get_release(&pkg).await?;
+            info!("Mirroring {}", release);
+
+            mirror.save_releases(&pkg.name, slice::from_ref(&release))?;
+            let urls = release_service
+                .mirror_release(&release, &platforms, &mirror)
+                .await?;
+            info!("{} mirrored: {} artifacts", release, urls.len());
+
+            pkg.version = None;
+            if !pkgs.iter().any(|it| it.name == pkg.name) {
+                pkgs.push(pkg);
+            }
+        }
+
+        let pkgs_file = output_dir.join(MIRROR_PKGS_FILE);
+        let mut saved_pkgs: Vec<Package> = if pkgs_file.exists() {
+            serde_yaml::from_reader(File::open(&pkgs_file)?)?
+        } else {
+            vec![]
+        };
+        saved_pkgs.retain(|it| !pkgs.iter().any(|pkg| pkg.name == it.name));
+        saved_pkgs.extend(pkgs);
+        serde_yaml::to_writer(File::create(&pkgs_file)?, &saved_pkgs)?;
+
+        info!(
+            "Mirror bundle saved to {:?}, add it on the offline machine by `huber repo add <name> --file <bundle>/{}`",
+            output_dir, MIRROR_PKGS_FILE
+        );
+
+        if self.archive {
+            let archive_file = output_dir.with_extension("tar.gz");
+            info!("Compressing the mirror bundle to {:?}", archive_file);
+            compress_tar_gz(&output_dir, &archive_file)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_platform(platform: &str) -> anyhow::Result<(String, String)> {
+    let (os, arch) = platform
+        .split_once('/')
+        .ok_or_else(|| anyhow!("Invalid platform {}, expected '<os>/<arch>'", platform))?;
+
+    let os = match os.to_lowercase().as_str() {
+        "darwin" | "macos" | "osx" => "macos".to_string(),
+        os => os.to_string(),
+    };
+    let arch = match arch.to_lowercase().as_str() {
+        "amd64" | "x64" | "x86_64" => "x86_64".to_string(),
+        "arm64" | "aarch64" => "aarch64".to_string(),
+        arch => arch.to_string(),
+    };
+
+    Ok((os, arch))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_platform() {
+        assert_eq!(
+            parse_platform("linux/amd64").unwrap(),
+            ("linux".to_string(), "x86_64".to_string())
+        );
+        assert_eq!(
+            parse_platform("Darwin/arm64").unwrap(),
+            ("macos".to_string(), "aarch64".to_string())
+        );
+        assert_eq!(
+            parse_platform("windows/x86_64").unwrap(),
+            ("windows".to_string(), "x86_64".to_string())
+        );
+        assert!(parse_platform("linux").is_err());
+    }
+}
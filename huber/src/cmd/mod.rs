@@ -1,19 +1,29 @@
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+use anyhow::anyhow;
 use async_trait::async_trait;
+use cache::CacheArgs;
 use clap::builder::PossibleValue;
 use clap::{Subcommand, ValueEnum};
 use clap_complete::Shell;
 use config::ConfigArgs;
 use current::CurrentArgs;
+use doctor::DoctorArgs;
+use env::EnvArgs;
 use flush::FlushArgs;
 use info::InfoArgs;
+use init::InitArgs;
 use install::InstallArgs;
+use mirror::MirrorArgs;
+use outdated::OutdatedArgs;
 use repo::RepoArgs;
 use reset::ResetArgs;
+use rollback::RollbackArgs;
+use run::RunArgs;
 use search::SearchArgs;
 use self_update::SelfUpdateArgs;
+use semver::VersionReq;
 use show::ShowArgs;
 use simpledi_rs::di::DIContainer;
 use uninstall::UninstallArgs;
@@ -22,22 +32,36 @@ use update::UpdateArgs;
 use crate::cmd::load::LoadArgs;
 use crate::cmd::lock::LockArgs;
 use crate::cmd::save::SaveArgs;
+use crate::cmd::sync::SyncArgs;
 use crate::cmd::unlock::UnlockArgs;
 use crate::model::config::Config;
+use crate::model::release::ReleaseKind;
+use crate::semver::find_max_satisfied_version;
+use crate::service::package::PackageService;
+use crate::service::ItemOperationAsyncTrait;
 
+pub mod cache;
 pub mod config;
 mod current;
+mod doctor;
+mod env;
 mod flush;
 mod info;
+mod init;
 mod install;
 mod load;
 pub mod lock;
+mod mirror;
+mod outdated;
 pub mod repo;
 mod reset;
+mod rollback;
+mod run;
 mod save;
 mod search;
 mod self_update;
 mod show;
+mod sync;
 mod uninstall;
 mod unlock;
 mod update;
@@ -111,6 +135,54 @@ pub enum Commands {
 
     #[command(about = "Unlock packages", bin_name = "unlock", bin_name = "unlock")]
     Unlock(UnlockArgs),
+
+    #[command(
+        about = "Sync installed packages with the project manifest and lockfile",
+        bin_name = "sync"
+    )]
+    Sync(SyncArgs),
+
+    #[command(about = "Manage the artifact download cache", bin_name = "cache")]
+    Cache(CacheArgs),
+
+    #[command(
+        about = "Mirror packages to a bundle for installing in offline mode",
+        bin_name = "mirror"
+    )]
+    Mirror(MirrorArgs),
+
+    #[command(
+        about = "Show installed packages with the latest versions of their release channels and locked versions",
+        bin_name = "outdated"
+    )]
+    Outdated(OutdatedArgs),
+
+    #[command(
+        about = "Roll back packages to the previous current versions",
+        bin_name = "rollback"
+    )]
+    Rollback(RollbackArgs),
+
+    #[command(about = "Check and repair the installed packages", bin_name = "doctor")]
+    Doctor(DoctorArgs),
+
+    #[command(
+        about = "Show the environment variables activating Huber and project versions",
+        bin_name = "env"
+    )]
+    Env(EnvArgs),
+
+    #[command(
+        about = "Show the shell integration script activating Huber",
+        bin_name = "init"
+    )]
+    Init(InitArgs),
+
+    #[command(
+        about = "Run a specific version of a package without changing the current version",
+        bin_name = "run"
+    )]
+    Run(RunArgs),
 }
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum PlatformStdLib {
@@ -207,6 +279,23 @@ pub fn get_updated_package_version(version: &str, latest_version: &str) -> Strin
     }
 }
 
+/// Get the highest release version of the package satisfying the version requirement
+pub async fn get_satisfied_package_version(
+    pkg_service: &PackageService,
+    pkg_name: &str,
+    requirement: &VersionReq,
+) -> anyhow::Result<String> {
+    let versions = pkg_service
+        .find(&pkg_name.to_string())
+        .await?
+        .into_iter()
+        .filter(|it| it.release_kind != Some(ReleaseKind::Draft))
+        .filter_map(|it| it.version);
+
+    find_max_satisfied_version(requirement, versions)
+        .ok_or_else(|| anyhow!("No release of {} matches {}", pkg_name, requirement))
+}
+
 pub fn get_default_stdlib() -> PlatformStdLib {
     #[cfg(target_os = "linux")]
     {
@@ -0,0 +1,137 @@
+use std::cmp::Ordering;
+use std::io::stdout;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::{Args, ValueHint};
+use libcli_rs::output;
+use libcli_rs::output::{OutputFactory, OutputTrait};
+use log::info;
+use simpledi_rs::di::{DIContainer, DIContainerTrait};
+use tokio::task::JoinHandle;
+
+use crate::cmd::update::is_pkg_locked_for_release;
+use crate::cmd::CommandTrait;
+use crate::error::HuberError::{PackageNotInstalled, PackagesOutdated};
+use crate::model::config::Config;
+use crate::model::release::{OutdatedRelease, ReleaseChannel, ReleaseKind};
+use crate::semver::VersionCompareTrait;
+use crate::service::cache::{CacheAsyncTrait, CacheService};
+use crate::service::package::PackageService;
+use crate::service::release::ReleaseService;
+use crate::service::ItemOperationTrait;
+
+#[derive(Args)]
+pub struct OutdatedArgs {
+    #[arg(help = "Package name", num_args = 1, value_hint = ValueHint::Unknown)]
+    name: Vec<String>,
+}
+
+#[async_trait]
+impl CommandTrait for OutdatedArgs {
+    async fn run(&self, config: &Config, container: &DIContainer) -> anyhow::Result<()> {
+        let release_service = Arc::new(container.get::<ReleaseService>().unwrap().clone());
+        let pkg_service = Arc::new(container.get::<PackageService>().unwrap().clone());
+        let cache_service = container.get::<CacheService>().unwrap();
+        let config = Arc::new(config.clone());
+
+        for name in self.name.iter() {
+            if !release_service.has(name)? {
+                return Err(anyhow!(PackageNotInstalled(name.clone())));
+            }
+        }
+
+        cache_service.update_repositories().await?;
+
+        let releases: Vec<_> = release_service
+            .list()?
+            .into_iter()
+            .filter(|it| self.name.is_empty() || self.name.contains(&it.name))
+            .collect();
+
+        // only the stable channel is resolvable by the latest releases in batches
+        let pkgs: Vec<_> = releases
+            .iter()
+            .filter(|it| matches!(it.package.channel, None | Some(ReleaseChannel::Stable)))
+            .filter_map(|it| pkg_service.get(&it.name).ok())
+            .collect();
+        let latest_releases = Arc::new(release_service.get_latest_releases(&pkgs).await);
+
+        let mut join_handles: Vec<JoinHandle<anyhow::Result<OutdatedRelease>>> = vec![];
+        for release in releases {
+            let release_service = release_service.clone();
+            let pkg_service = pkg_service.clone();
+            let config = config.clone();
+            let latest_releases = latest_releases.clone();
+
+            join_handles.push(tokio::spawn(async move {
+                let pkg = pkg_service.get(&release.name)?;
+
+                // the latest release of the channel the installed release was resolved from
+                let channel = release
+                    .package
+                    .channel
+                    .clone()
+                    .unwrap_or(ReleaseChannel::Stable);
+                let releases = match latest_releases.get(&pkg.name) {
+                    Some(releases) => releases.clone(),
+                    _ => release_service.get_releases(&pkg).await?,
+                };
+                let latest = releases
+                    .iter()
+                    .find(|it| channel.matches(it))
+                    .map(|it| it.version.clone());
+                // only the pre-release newer than the latest release is worth mentioning
+                let latest_prerelease = releases
+                    .iter()
+                    .find(|it| it.kind == Some(ReleaseKind::PreRelease))
+                    .map(|it| it.version.clone())
+                    .filter(|it| {
+                        latest
+                            .as_ref()
+                            .is_none_or(|latest| it.cmp_version(latest) == Some(Ordering::Greater))
+                    });
+
+                let outdated = latest.as_ref().is_some_and(|latest| {
+                    latest.cmp_version(&release.version) == Some(Ordering::Greater)
+                        && !is_pkg_locked_for_release(&config, &pkg, latest)
+                });
+
+                Ok(OutdatedRelease {
+                    current: release.version,
+                    latest,
+                    latest_prerelease,
+                    lock: config.lock_pkg_versions.get(&pkg.name).cloned(),
+                    outdated,
+                    name: release.name,
+                })
+            }));
+        }
+
+        let mut outdated_releases = vec![];
+        for handle in join_handles.into_iter() {
+            outdated_releases.push(handle.await??);
+        }
+        outdated_releases.sort_by(|x, y| x.name.cmp(&y.name));
+
+        if outdated_releases.is_empty() {
+            info!("No packages installed");
+            return Ok(());
+        }
+
+        output!(config.output_format, .display(
+            stdout(),
+            &outdated_releases,
+            None,
+            None,
+        ))?;
+
+        let count = outdated_releases.iter().filter(|it| it.outdated).count();
+        if count > 0 {
+            return Err(anyhow!(PackagesOutdated(count)));
+        }
+
+        Ok(())
+    }
+}
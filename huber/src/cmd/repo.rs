@@ -1,3 +1,4 @@
+use std::fs::canonicalize;
 use std::io::stdout;
 use std::path::PathBuf;
 
@@ -75,7 +76,10 @@ impl CommandTrait for RepoAddArgs {
         let repo = Repository {
             name: self.name.clone(),
             url: self.url.clone(),
-            file: self.file.clone().map(PathBuf::from),
+            file: self
+                .file
+                .as_ref()
+                .map(|it| canonicalize(it).unwrap_or_else(|_| PathBuf::from(it))),
         };
         info!("Adding repo {}", repo.name);
         if let Err(err) = repo_service.create(repo.clone()).await {
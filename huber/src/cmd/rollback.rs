@@ -0,0 +1,48 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::{Args, ValueHint};
+use log::info;
+use simpledi_rs::di::{DIContainer, DIContainerTrait};
+
+use crate::cmd::CommandTrait;
+use crate::error::HuberError::PackageNotInstalled;
+use crate::lock_huber_ops;
+use crate::model::config::Config;
+use crate::service::package::PackageService;
+use crate::service::release::ReleaseService;
+use crate::service::ItemOperationTrait;
+
+#[derive(Args)]
+pub struct RollbackArgs {
+    #[arg(
+        help = "Package name",
+        num_args = 1,
+        required = true,
+        value_hint = ValueHint::Unknown
+    )]
+    name: Vec<String>,
+}
+
+#[async_trait]
+impl CommandTrait for RollbackArgs {
+    async fn run(&self, config: &Config, container: &DIContainer) -> anyhow::Result<()> {
+        lock_huber_ops!(config);
+
+        let release_service = container.get::<ReleaseService>().unwrap();
+        let pkg_service = container.get::<PackageService>().unwrap();
+
+        for name in self.name.iter() {
+            if !release_service.has(name)? {
+                return Err(anyhow!(PackageNotInstalled(name.clone())));
+            }
+
+            let pkg = pkg_service.get(name)?;
+            info!("Rolling back {} to the previous current version", name);
+
+            let release = release_service.rollback(&pkg).await?;
+            info!("{}@{} is now the current version", name, release.version);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::{exit, Command};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::{Args, ValueHint};
+use log::{debug, info};
+use simpledi_rs::di::{DIContainer, DIContainerTrait};
+
+use crate::cmd::{
+    get_default_stdlib, get_satisfied_package_version, get_updated_package_version, CommandTrait,
+    PlatformStdLib,
+};
+use crate::error::HuberError::PackageNotFound;
+use crate::lock_huber_ops;
+use crate::model::config::Config;
+use crate::model::package::Package;
+use crate::model::release::Release;
+use crate::parse::parse_pkg_name_optional_semver;
+use crate::semver::parse_version_req;
+use crate::service::package::PackageService;
+use crate::service::release::{ReleaseService, ReleaseTrait};
+use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait};
+use crate::shim::{exec, match_version};
+
+#[derive(Args)]
+pub struct RunArgs {
+    #[arg(
+        help = "Package name (e.g. 'package-name', 'package-name@version', 'package-name@~1.29'); Default to the current version, or the latest version if not installed",
+        num_args = 1,
+        required = true,
+        value_parser = parse_pkg_name_optional_semver,
+        value_hint = ValueHint::Unknown,
+    )]
+    name_version: (String, String),
+
+    #[arg(
+        help = "Executable to run if the package has multiple executables; Default to the one named as the package",
+        short,
+        long,
+        num_args = 1,
+        value_hint = ValueHint::Unknown,
+    )]
+    executable: Option<String>,
+
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    #[arg(
+        help = "Prefer standard library (only for Linux or Windows)",
+        long,
+        num_args = 1,
+        default_value_t = get_default_stdlib(),
+        value_enum
+    )]
+    prefer_stdlib: PlatformStdLib,
+
+    #[cfg(target_os = "macos")]
+    #[arg(
+        help = "Prefer standard library (only for Linux or Windows)",
+        long,
+        hide = true,
+        num_args = 1,
+        default_value_t = get_default_stdlib(),
+        value_enum
+    )]
+    prefer_stdlib: PlatformStdLib,
+
+    #[arg(help = "Arguments passed to the executable", last = true)]
+    args: Vec<OsString>,
+}
+
+#[async_trait]
+impl CommandTrait for RunArgs {
+    async fn run(&self, config: &Config, container: &DIContainer) -> anyhow::Result<()> {
+        let release_service = container.get::<ReleaseService>().unwrap();
+        let pkg_service = container.get::<PackageService>().unwrap();
+
+        let (name, version) = &self.name_version;
+        if !pkg_service.has(name)? {
+            return Err(anyhow!(PackageNotFound(name.clone())));
+        }
+        let pkg = pkg_service.get(name)?;
+
+        let release = match find_installed_release(release_service, &pkg, version).await? {
+            Some(release) => release,
+            None => {
+                install_release(
+                    config,
+                    release_service,
+                    pkg_service,
+                    pkg,
+                    version,
+                    &self.prefer_stdlib,
+                )
+                .await?
+            }
+        };
+
+        let executables = release_service.get_executables(&release.package, &release.version)?;
+        let (exec_name, exec_path) =
+            select_executable(&release, executables, self.executable.as_deref())?;
+
+        let mut command = Command::new(&exec_path);
+        command.args(&self.args);
+        if let Some(release_env) = release_service.get_release_env(&release)? {
+            command.envs(release_env.vars);
+
+            if !release_env.path_dirs.is_empty() {
+                let path_dirs = env::var_os("PATH")
+                    .map(|it| env::split_paths(&it).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                command.env(
+                    "PATH",
+                    env::join_paths(release_env.path_dirs.into_iter().chain(path_dirs))?,
+                );
+            }
+        }
+
+        debug!("Running {:?} of {}", exec_path, release);
+        let code = exec(command, OsString::from(exec_name))?;
+        exit(code);
+    }
+}
+
+// find the installed release matching the version or the version requirement, or the current release if not specified
+async fn find_installed_release(
+    release_service: &ReleaseService,
+    pkg: &Package,
+    version: &str,
+) -> anyhow::Result<Option<Release>> {
+    if version.is_empty() {
+        return Ok(release_service.current(pkg).ok());
+    }
+
+    let releases = release_service.find(pkg).await?;
+    let versions: Vec<_> = releases.iter().map(|it| it.version.clone()).collect();
+
+    Ok(match_version(version, &versions)
+        .and_then(|version| releases.into_iter().find(|it| it.version == version)))
+}
+
+// install the release without setting it as current, so the current release is kept
+async fn install_release(
+    config: &Config,
+    release_service: &ReleaseService,
+    pkg_service: &PackageService,
+    mut pkg: Package,
+    version: &str,
+    prefer_stdlib: &PlatformStdLib,
+) -> anyhow::Result<Release> {
+    lock_huber_ops!(config);
+
+    let version = match parse_version_req(version) {
+        Some(requirement) => {
+            get_satisfied_package_version(pkg_service, &pkg.name, &requirement).await?
+        }
+        None => {
+            let latest_version = release_service.get_latest(&pkg).await?.version;
+            if version.is_empty() {
+                latest_version
+            } else {
+                get_updated_package_version(version, &latest_version)
+            }
+        }
+    };
+
+    info!(
+        "Installing {}@{} without setting it as current",
+        pkg.name, version
+    );
+    pkg.version = Some(version);
+    release_service
+        .update(&pkg, prefer_stdlib, true, false)
+        .await
+}
+
+// select the executable by the name, or the only one, or the one named as the package
+fn select_executable(
+    release: &Release,
+    mut executables: HashMap<String, PathBuf>,
+    name: Option<&str>,
+) -> anyhow::Result<(String, PathBuf)> {
+    let name = match name {
+        Some(name) => name.to_string(),
+        None if executables.len() == 1 => executables.keys().next().unwrap().clone(),
+        None => release.package.name.clone(),
+    };
+
+    if let Some(found) = executables
+        .remove_entry(&name)
+        .or_else(|| executables.remove_entry(&format!("{}.exe", name)))
+    {
+        return Ok(found);
+    }
+
+    let mut names: Vec<_> = executables.into_keys().collect();
+    names.sort();
+    Err(anyhow!(
+        "Executable {} not found in {}; Specify one of {:?} by --executable",
+        name,
+        release,
+        names
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::release::{tool_release, ReleaseKind};
+
+    #[test]
+    fn test_select_executable() {
+        let release = tool_release("v1.0.0", ReleaseKind::Release);
+        let executables = HashMap::from([
+            ("tool".to_string(), PathBuf::from("/tool/tool")),
+            (
+                "tool-plugin".to_string(),
+                PathBuf::from("/tool/tool-plugin"),
+            ),
+        ]);
+
+        let (name, _) = select_executable(&release, executables.clone(), None).unwrap();
+        assert_eq!(name, "tool");
+
+        let (name, path) =
+            select_executable(&release, executables.clone(), Some("tool-plugin")).unwrap();
+        assert_eq!(name, "tool-plugin");
+        assert_eq!(path, PathBuf::from("/tool/tool-plugin"));
+
+        assert!(select_executable(&release, executables, Some("kubectl")).is_err());
+
+        let executables = HashMap::from([("tool2".to_string(), PathBuf::from("/tool/tool2"))]);
+        let (name, _) = select_executable(&release, executables, None).unwrap();
+        assert_eq!(name, "tool2");
+    }
+}
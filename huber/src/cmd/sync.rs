@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use clap::{Args, ValueHint};
+use log::{info, warn};
+use simpledi_rs::di::{DIContainer, DIContainerTrait};
+
+use crate::cmd::{
+    get_satisfied_package_version, get_updated_package_version, CommandTrait, PlatformStdLib,
+};
+use crate::error::HuberError::PackageNotFound;
+use crate::lock_huber_ops;
+use crate::model::config::Config;
+use crate::model::package::Package;
+use crate::model::project::{
+    LockedPackage, ProjectLock, ProjectManifest, PROJECT_LOCK_FILE, PROJECT_MANIFEST_FILE,
+};
+use crate::semver::{is_version_satisfied, parse_version_req};
+use crate::service::cache::{CacheAsyncTrait, CacheService};
+use crate::service::package::PackageService;
+use crate::service::release::{ReleaseAsyncTrait, ReleaseService};
+use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait};
+
+#[derive(Args)]
+pub struct SyncArgs {
+    #[arg(
+        help = "Project manifest file. The lockfile is located in the same directory",
+        long,
+        num_args = 1,
+        default_value = PROJECT_MANIFEST_FILE,
+        value_hint = ValueHint::FilePath
+    )]
+    file: String,
+
+    #[arg(
+        help = "Require the lockfile to be up to date instead of updating it",
+        long,
+        num_args = 0
+    )]
+    locked: bool,
+
+    #[arg(
+        help = "Uninstall the installed packages not listed in the project manifest",
+        long,
+        num_args = 0
+    )]
+    prune: bool,
+}
+
+#[async_trait]
+impl CommandTrait for SyncArgs {
+    async fn run(&self, config: &Config, container: &DIContainer) -> anyhow::Result<()> {
+        lock_huber_ops!(config);
+
+        let release_service = container.get::<ReleaseService>().unwrap();
+        let pkg_service = container.get::<PackageService>().unwrap();
+        let cache_service = container.get::<CacheService>().unwrap();
+
+        let manifest_file = PathBuf::from(&self.file);
+        let lock_file = manifest_file.with_file_name(PROJECT_LOCK_FILE);
+
+        info!("Loading the project manifest {:?}", manifest_file);
+        let manifest = ProjectManifest::load(&manifest_file)?;
+        let old_lock = ProjectLock::load(&lock_file)?;
+
+        if self.locked && !is_lock_up_to_date(&manifest, &old_lock) {
+            return Err(anyhow!(
+                "{:?} needs to be updated, but --locked was specified",
+                lock_file
+            ));
+        }
+
+        cache_service.update_repositories().await?;
+
+        let mut lock = ProjectLock::default();
+        for (name, requirement) in manifest.packages.iter() {
+            let locked = old_lock
+                .get(name)
+                .filter(|it| is_version_satisfied(requirement, &it.version));
+
+            let locked =
+                sync_package(pkg_service, release_service, name, requirement, locked).await?;
+            lock.packages.push(locked);
+        }
+
+        if lock != old_lock {
+            info!("Updating the lockfile {:?}", lock_file);
+            lock.save(&lock_file)?;
+        }
+
+        if self.prune {
+            for release in release_service.list()? {
+                if manifest.packages.contains_key(&release.name) {
+                    continue;
+                }
+
+                info!(
+                    "Uninstalling {}, because it's not in the project manifest",
+                    release.name
+                );
+                release_service.delete(&release.name)?;
+            }
+        }
+
+        info!("Packages synced: total {}", lock.packages.len());
+        Ok(())
+    }
+}
+
+fn is_lock_up_to_date(manifest: &ProjectManifest, lock: &ProjectLock) -> bool {
+    lock.packages.len() == manifest.packages.len()
+        && manifest.packages.iter().all(|(name, requirement)| {
+            lock.get(name)
+                .is_some_and(|it| is_version_satisfied(requirement, &it.version))
+        })
+}
+
+async fn sync_package(
+    pkg_service: &PackageService,
+    release_service: &ReleaseService,
+    name: &str,
+    requirement: &str,
+    locked: Option<&LockedPackage>,
+) -> anyhow::Result<LockedPackage> {
+    if !pkg_service.has(name)? {
+        return Err(anyhow!(PackageNotFound(name.to_string())));
+    }
+
+    let mut pkg = pkg_service.get(name)?;
+    let version = match locked {
+        Some(locked) => locked.version.clone(),
+        None => resolve_version(pkg_service, release_service, &pkg, requirement).await?,
+    };
+
+    let installed_release = release_service
+        .find(&pkg)
+        .await?
+        .into_iter()
+        .find(|it| it.version == version);
+
+    let release = match installed_release {
+        Some(mut release) => {
+            if release.current {
+                info!("{}@{} already installed", name, version);
+            } else {
+                info!("Updating the current version of {} to {}", name, version);
+                release_service.set_current(&mut release).await?;
+            }
+            release
+        }
+        None => {
+            info!("Installing package {}@{}", name, version);
+            pkg.version = Some(version.clone());
+
+            match locked {
+                Some(locked) if !locked.assets.is_empty() => {
+                    release_service
+                        .update_from_assets(&pkg, &locked.assets)
+                        .await?
+                }
+                _ => {
+                    release_service
+                        .update(&pkg, &PlatformStdLib::None, true, true)
+                        .await?
+                }
+            }
+        }
+    };
+
+    if let Some(locked) = locked {
+        return Ok(locked.clone());
+    }
+
+    let assets = release.assets.unwrap_or_default();
+    if assets.is_empty() {
+        warn!(
+            "No downloaded assets recorded for {}@{}, reinstall it to lock the assets",
+            name, version
+        );
+    }
+
+    Ok(LockedPackage {
+        name: name.to_string(),
+        version,
+        assets,
+    })
+}
+
+pub(crate) async fn resolve_version(
+    pkg_service: &PackageService,
+    release_service: &ReleaseService,
+    pkg: &Package,
+    requirement: &str,
+) -> anyhow::Result<String> {
+    let requirement = requirement.trim();
+
+    if requirement.is_empty() || requirement == "latest" {
+        return Ok(release_service.get_latest(pkg).await?.version);
+    }
+
+    // an exact release tag, prefixed by 'v' like the latest one if it's a version (e.g. 1.29.3, not nightly)
+    let Some(requirement) = parse_version_req(requirement) else {
+        if !requirement.starts_with(|it: char| it.is_ascii_digit()) {
+            return Ok(requirement.to_string());
+        }
+
+        let latest_release = release_service.get_latest(pkg).await?;
+        return Ok(get_updated_package_version(
+            requirement,
+            &latest_release.version,
+        ));
+    };
+
+    get_satisfied_package_version(pkg_service, &pkg.name, &requirement).await
+}
@@ -9,7 +9,7 @@ use crate::lock_huber_ops;
 use crate::model::config::Config;
 use crate::service::package::PackageService;
 use crate::service::release::ReleaseService;
-use crate::service::{check_pkg_installed, ItemOperationTrait};
+use crate::service::{check_pkg_versions_installed, ItemOperationTrait};
 
 #[derive(Args)]
 pub struct UninstallArgs {
@@ -26,7 +26,7 @@ impl CommandTrait for UninstallArgs {
         let release_service = container.get::<ReleaseService>().unwrap();
 
         for name in self.name.iter() {
-            if let Err(e) = check_pkg_installed(pkg_service, release_service, name) {
+            if let Err(e) = check_pkg_versions_installed(pkg_service, release_service, name) {
                 warn!("Skipped uninstalling package {}: {}", name, e);
                 continue;
             }
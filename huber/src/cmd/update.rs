@@ -17,7 +17,7 @@ use crate::error::HuberError::{PackageNotInstalled, PackageUnableToUpdate};
 use crate::lock_huber_ops;
 use crate::model::config::Config;
 use crate::model::package::Package;
-use crate::model::release::Release;
+use crate::model::release::{Release, ReleaseChannel};
 use crate::service::package::PackageService;
 use crate::service::release::ReleaseService;
 use crate::service::ItemOperationTrait;
@@ -83,12 +83,28 @@ impl CommandTrait for UpdateArgs {
                 .collect()
         };
 
+        // only the stable channel is resolvable by the latest releases in batches
+        let pkgs: Vec<_> = installed_latest_pkg_releases
+            .iter()
+            .filter(|(_, release)| {
+                matches!(release.package.channel, None | Some(ReleaseChannel::Stable))
+            })
+            .filter_map(|(name, release)| {
+                pkg_service.get(name).ok().map(|pkg| Package {
+                    channel: release.package.channel.clone(),
+                    ..pkg
+                })
+            })
+            .collect();
+        let latest_releases = Arc::new(release_service.get_latest_releases(&pkgs).await);
+
         let mut join_handles: Vec<JoinHandle<anyhow::Result<()>>> = vec![];
 
         for (name, installed_release) in installed_latest_pkg_releases {
             let release_service = release_service.clone();
             let pkg_service = pkg_service.clone();
             let config = config.clone();
+            let latest_releases = latest_releases.clone();
             let dryrun = self.dryrun;
             let prefer_stdlib = self.prefer_stdlib;
 
@@ -98,14 +114,21 @@ impl CommandTrait for UpdateArgs {
                     name, installed_release.version
                 );
 
-                let pkg = pkg_service.get(&name)?;
-                let new_release = release_service.get_latest(&pkg).await.map_err(|err| {
-                    anyhow!(
-                        "Failed to get the latest release of package {}: {}",
-                        name,
-                        err
-                    )
-                })?;
+                let mut pkg = pkg_service.get(&name)?;
+                pkg.channel = installed_release.package.channel.clone();
+                let new_release = match latest_releases
+                    .get(&name)
+                    .and_then(|it| it.iter().find(|it| ReleaseChannel::Stable.matches(it)))
+                {
+                    Some(release) => release.clone(),
+                    None => release_service.get_latest(&pkg).await.map_err(|err| {
+                        anyhow!(
+                            "Failed to get the latest release of package {}: {}",
+                            name,
+                            err
+                        )
+                    })?,
+                };
 
                 info!(
                     "Found the latest version of {}: {}",
@@ -206,9 +229,10 @@ async fn update(
     if dryrun {
         info!("Available update {} to {}", installed_release, new_release);
     } else {
+        // the previous installation and current release are restored if the update fails
         info!("Updating {} to {}", installed_release, new_release);
         release_service
-            .update(&new_release.package, prefer_stdlib, true)
+            .update(&new_release.package, prefer_stdlib, true, true)
             .await?;
     }
 
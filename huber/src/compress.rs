@@ -4,7 +4,9 @@ use std::io::BufReader;
 use std::path::Path;
 
 use flate2::read::GzDecoder;
-use tar::Archive;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tar::{Archive, Builder};
 use xz2::read::XzDecoder;
 use zip::ZipArchive;
 
@@ -35,6 +37,20 @@ pub fn uncompress_archive(file: &Path, extract_dir: &Path, ext: &str) -> anyhow:
     Ok(())
 }
 
+/// Compress the directory into a tar.gz archive, the entries are prefixed with the directory name
+pub fn compress_tar_gz(dir: &Path, file: &Path) -> anyhow::Result<()> {
+    let name = dir
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid directory to compress: {:?}", dir))?;
+
+    let tar_gz = File::create(file)?;
+    let mut builder = Builder::new(GzEncoder::new(tar_gz, Compression::default()));
+    builder.append_dir_all(name, dir)?;
+    builder.into_inner()?.finish()?;
+
+    Ok(())
+}
+
 fn untar_xz(file: &Path, extract_dir: &Path) -> anyhow::Result<()> {
     let tar_xz = File::open(file)?;
     let tar = XzDecoder::new(BufReader::new(tar_xz));
@@ -84,3 +100,28 @@ fn ungz(file: &Path, extract_dir: &Path) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs::{create_dir_all, read_to_string, write};
+
+    use super::*;
+
+    #[test]
+    fn test_compress_tar_gz() {
+        let dir = tempfile::tempdir().unwrap();
+        let bundle_dir = dir.path().join("bundle");
+        create_dir_all(bundle_dir.join("releases")).unwrap();
+        write(bundle_dir.join("releases/tool.yaml"), "[]").unwrap();
+
+        let file = dir.path().join("bundle.tar.gz");
+        compress_tar_gz(&bundle_dir, &file).unwrap();
+
+        let extract_dir = dir.path().join("extract");
+        uncompress_archive(&file, &extract_dir, "tar.gz").unwrap();
+        assert_eq!(
+            read_to_string(extract_dir.join("bundle/releases/tool.yaml")).unwrap(),
+            "[]"
+        );
+    }
+}
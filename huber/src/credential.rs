@@ -0,0 +1,232 @@
+use std::env;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+use log::{debug, warn};
+use reqwest::RequestBuilder;
+use url::Url;
+
+use crate::model::config::HostCredential;
+use crate::model::package::host_name;
+
+/// Credential resolved from the configured host credentials
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub login: Option<String>,
+    pub token: String,
+}
+
+impl Credential {
+    pub fn new(token: &str) -> Self {
+        Self {
+            login: None,
+            token: token.to_string(),
+        }
+    }
+
+    /// Authorize the request by the basic auth if the login is available, otherwise by the bearer token
+    pub fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.login {
+            Some(login) => request.basic_auth(login, Some(&self.token)),
+            None => request.bearer_auth(&self.token),
+        }
+    }
+}
+
+impl HostCredential {
+    fn matches(&self, host: &str, owner: Option<&str>) -> bool {
+        host_name(&self.host) == host
+            && match (&self.owner, owner) {
+                (None, _) => true,
+                (Some(expected), Some(owner)) => expected.eq_ignore_ascii_case(owner),
+                (Some(_), None) => false,
+            }
+    }
+
+    /// Resolve the credential from the token, the environment variable or the netrc file in order
+    pub fn resolve(&self) -> Option<Credential> {
+        if let Some(token) = &self.token {
+            return Some(Credential::new(token));
+        }
+
+        if let Some(name) = &self.token_env {
+            match env::var(name) {
+                Ok(token) if !token.is_empty() => return Some(Credential::new(&token)),
+                _ => debug!(
+                    "Environment variable {} of {} credential not set",
+                    name, self.host
+                ),
+            }
+        }
+
+        if let Some(file) = &self.netrc_file {
+            let path = match file.strip_prefix("~/") {
+                Some(file) => dirs::home_dir().unwrap_or_default().join(file),
+                None => PathBuf::from(file),
+            };
+
+            match read_to_string(&path) {
+                Ok(content) => return parse_netrc(&content, &host_name(&self.host)),
+                Err(e) => warn!("Failed to read netrc file {:?}: {}", path, e),
+            }
+        }
+
+        None
+    }
+}
+
+/// Find the credential of the host, the one configured for the owner takes precedence
+pub fn find_credential(
+    credentials: &[HostCredential],
+    host: &str,
+    owner: Option<&str>,
+) -> Option<Credential> {
+    let host = host_name(host);
+    let (owner_credentials, host_credentials): (Vec<_>, Vec<_>) = credentials
+        .iter()
+        .filter(|it| it.matches(&host, owner))
+        .partition(|it| it.owner.is_some());
+
+    owner_credentials
+        .into_iter()
+        .chain(host_credentials)
+        .find_map(|it| it.resolve())
+}
+
+/// Find the credential of the URL, the first path segment is regarded as the owner
+pub fn find_url_credential(credentials: &[HostCredential], url: &str) -> Option<Credential> {
+    let url = Url::parse(url).ok()?;
+    let owner = url
+        .path_segments()
+        .and_then(|mut it| it.next())
+        .filter(|it| !it.is_empty());
+
+    find_credential(credentials, url.as_str(), owner)
+}
+
+/// Parse the credential of the machine from the netrc content, `default` is used if no machine matched
+pub fn parse_netrc(content: &str, host: &str) -> Option<Credential> {
+    let mut tokens = content.split_whitespace();
+    let mut matched: Option<Credential> = None;
+    let mut default: Option<Credential> = None;
+    let mut current: Option<(bool, Credential)> = None;
+
+    let mut finish = |entry: Option<(bool, Credential)>| {
+        if let Some((is_default, credential)) = entry {
+            if credential.token.is_empty() {
+                return;
+            }
+            if is_default {
+                default.get_or_insert(credential);
+            } else {
+                matched.get_or_insert(credential);
+            }
+        }
+    };
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" => {
+                finish(current.take());
+                if tokens.next().is_some_and(|it| it == host) {
+                    current = Some((false, Credential::new("")));
+                }
+            }
+            "default" => {
+                finish(current.take());
+                current = Some((true, Credential::new("")));
+            }
+            "login" | "password" | "account" => {
+                let value = tokens.next().unwrap_or_default().to_string();
+                if let Some((_, credential)) = current.as_mut() {
+                    match token {
+                        "login" => credential.login = Some(value),
+                        "password" => credential.token = value,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    finish(current.take());
+
+    matched.or(default)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn credential(host: &str, owner: Option<&str>, token: &str) -> HostCredential {
+        HostCredential {
+            host: host.to_string(),
+            owner: owner.map(|it| it.to_string()),
+            token: Some(token.to_string()),
+            token_env: None,
+            netrc_file: None,
+        }
+    }
+
+    #[test]
+    fn test_find_credential() {
+        let credentials = vec![
+            credential("github.com", None, "default"),
+            credential("https://github.com", Some("org"), "org"),
+            credential("gitea.example.com:3000", None, "gitea"),
+        ];
+
+        assert_eq!(
+            find_credential(&credentials, "github.com", Some("org")),
+            Some(Credential::new("org"))
+        );
+        assert_eq!(
+            find_credential(&credentials, "github.com", Some("another")),
+            Some(Credential::new("default"))
+        );
+        assert_eq!(
+            find_credential(&credentials, "http://gitea.example.com:3000", None),
+            Some(Credential::new("gitea"))
+        );
+        assert_eq!(find_credential(&credentials, "gitlab.com", None), None);
+
+        assert_eq!(
+            find_url_credential(
+                &credentials,
+                "https://github.com/ORG/tool/releases/download/v1.0.0/tool.tar.gz"
+            ),
+            Some(Credential::new("org"))
+        );
+    }
+
+    #[test]
+    fn test_parse_netrc() {
+        let content = r#"
+machine example.com login user password secret
+machine downloads.example.com
+  login admin
+  password admin-secret
+default login anonymous password anonymous-secret
+"#;
+
+        assert_eq!(
+            parse_netrc(content, "downloads.example.com"),
+            Some(Credential {
+                login: Some("admin".to_string()),
+                token: "admin-secret".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_netrc(content, "example.com").map(|it| it.token),
+            Some("secret".to_string())
+        );
+        assert_eq!(
+            parse_netrc(content, "another.com").map(|it| it.token),
+            Some("anonymous-secret".to_string())
+        );
+        assert_eq!(
+            parse_netrc("machine example.com login user", "example.com"),
+            None
+        );
+    }
+}
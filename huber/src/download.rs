@@ -0,0 +1,244 @@
+use std::fs::{metadata, remove_file, rename, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use log::{debug, warn};
+use reqwest::header::RANGE;
+use reqwest::{RequestBuilder, StatusCode};
+use sha2::{Digest, Sha256};
+
+use crate::model::config::DownloadRetryPolicy;
+
+/// Download the URL to the file by streaming the response to a partial file first.
+/// The partial file is resumed by the HTTP Range request, and failed downloads are retried by the policy.
+pub async fn download_file<F, Fut>(
+    url: &str,
+    path: &Path,
+    policy: &DownloadRetryPolicy,
+    progress: Option<&MultiProgress>,
+    new_request: F,
+) -> anyhow::Result<()>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<RequestBuilder>>,
+{
+    let part_path = partial_file_path(url, path);
+    let mut attempt = 0;
+
+    loop {
+        match download_partial_file(url, &part_path, progress, &new_request).await {
+            Ok(_) => break,
+            Err(e) if attempt < policy.retries && is_retryable(&e) => {
+                let backoff = policy.backoff(attempt);
+                warn!(
+                    "Failed to download {}, retrying in {:?}: {}",
+                    url, backoff, e
+                );
+
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    rename(&part_path, path)?;
+    Ok(())
+}
+
+async fn download_partial_file<F, Fut>(
+    url: &str,
+    part_path: &Path,
+    progress: Option<&MultiProgress>,
+    new_request: &F,
+) -> anyhow::Result<()>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = anyhow::Result<RequestBuilder>>,
+{
+    let (mut response, offset) = loop {
+        let offset = metadata(part_path).map_or(0, |it| it.len());
+
+        let mut request = new_request().await?;
+        if offset > 0 {
+            debug!("Resuming the download of {} from {} bytes", url, offset);
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let response = request.send().await?;
+        if offset > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            debug!(
+                "Downloading {} from scratch, because the range is not satisfiable",
+                url
+            );
+            remove_file(part_path)?;
+            continue;
+        }
+
+        let response = response.error_for_status()?;
+        let offset = if response.status() == StatusCode::PARTIAL_CONTENT {
+            offset
+        } else {
+            0
+        };
+
+        break (response, offset);
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(offset > 0)
+        .truncate(offset == 0)
+        .open(part_path)?;
+
+    let progress_bar = progress.map(|it| {
+        it.add(new_progress_bar(
+            url,
+            response.content_length().map(|len| len + offset),
+        ))
+    });
+    if let Some(progress_bar) = &progress_bar {
+        progress_bar.set_position(offset);
+    }
+
+    while let Some(chunk) = response.chunk().await? {
+        file.write_all(&chunk)?;
+        if let Some(progress_bar) = &progress_bar {
+            progress_bar.inc(chunk.len() as u64);
+        }
+    }
+    file.flush()?;
+
+    if let Some(progress_bar) = progress_bar {
+        progress_bar.finish();
+    }
+
+    Ok(())
+}
+
+fn new_progress_bar(url: &str, len: Option<u64>) -> ProgressBar {
+    let name = url.rsplit('/').next().unwrap_or(url).to_string();
+
+    match len {
+        Some(len) => ProgressBar::new(len).with_style(
+            ProgressStyle::with_template(
+                "{msg} [{bar:40}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+            )
+            .unwrap()
+            .progress_chars("=> "),
+        ),
+        None => ProgressBar::new_spinner().with_style(
+            ProgressStyle::with_template("{spinner} {msg} {bytes} ({bytes_per_sec})").unwrap(),
+        ),
+    }
+    .with_message(name)
+}
+
+// the partial file is named after the URL, so a stale one of another URL is never resumed
+fn partial_file_path(url: &str, path: &Path) -> PathBuf {
+    let hash = format!("{:x}", Sha256::digest(url.as_bytes()));
+    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+
+    path.with_file_name(format!("{}.{}.part", filename, &hash[..16]))
+}
+
+// network errors, server errors, timeouts and rate limits are transient
+fn is_retryable(err: &anyhow::Error) -> bool {
+    match err.downcast_ref::<reqwest::Error>() {
+        Some(e) => match e.status() {
+            Some(status) => {
+                status.is_server_error()
+                    || status == StatusCode::REQUEST_TIMEOUT
+                    || status == StatusCode::TOO_MANY_REQUESTS
+            }
+            None => !e.is_builder() && !e.is_redirect(),
+        },
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{read, write};
+
+    use super::*;
+
+    const CONTENT: &[u8] = b"hello world";
+
+    #[tokio::test]
+    async fn test_download_file() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/pkg.tar.gz")
+            .with_body(CONTENT)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pkg.tar.gz");
+        let url = format!("{}/pkg.tar.gz", server.url());
+
+        download_file(&url, &path, &Default::default(), None, || async {
+            Ok(reqwest::Client::new().get(&url))
+        })
+        .await
+        .unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(read(&path).unwrap(), CONTENT);
+        assert!(!partial_file_path(&url, &path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_resume_download_file() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/pkg.tar.gz")
+            .match_header("Range", "bytes=6-")
+            .with_status(206)
+            .with_body(&CONTENT[6..])
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pkg.tar.gz");
+        let url = format!("{}/pkg.tar.gz", server.url());
+        write(partial_file_path(&url, &path), &CONTENT[..6]).unwrap();
+
+        download_file(&url, &path, &Default::default(), None, || async {
+            Ok(reqwest::Client::new().get(&url))
+        })
+        .await
+        .unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(read(&path).unwrap(), CONTENT);
+    }
+
+    #[tokio::test]
+    async fn test_download_file_not_found() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/pkg.tar.gz")
+            .with_status(404)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pkg.tar.gz");
+        let url = format!("{}/pkg.tar.gz", server.url());
+
+        let result = download_file(&url, &path, &Default::default(), None, || async {
+            Ok(reqwest::Client::new().get(&url))
+        })
+        .await;
+        mock.assert_async().await;
+
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+}
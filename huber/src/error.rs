@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,6 +19,51 @@ pub enum HuberError {
     #[error("Repository not found: {0:?}")]
     RepoNotFound(String),
 
+    #[error("Outdated packages found: {0}")]
+    PackagesOutdated(usize),
+
+    #[error("Problems found: {0}; Run with --fix to repair")]
+    DoctorProblemsFound(usize),
+
+    #[error("No previous version to roll back: {0:?}")]
+    NoRollbackRelease(String),
+
+    #[error("Project version not installed: {0:?} required by {1:?}")]
+    ProjectVersionNotInstalled(String, PathBuf),
+
     #[error("Package unable to update")]
     PackageUnableToUpdate(#[source] anyhow::Error),
+
+    #[error("Checksum not found: {0:?}")]
+    ChecksumNotFound(String),
+
+    #[error("Checksum mismatch: {artifact:?} (expected: {expected}, actual: {actual})")]
+    ChecksumMismatch {
+        artifact: String,
+        expected: String,
+        actual: String,
+    },
+
+    #[error("Signature not found: {0:?}")]
+    SignatureNotFound(String),
+
+    #[error("Signature verification failed: {0:?}")]
+    SignatureVerificationFailed(String, #[source] anyhow::Error),
+
+    #[error(
+        "GitHub API rate limit exceeded until {0}; Set a GitHub token to raise the rate limit"
+    )]
+    GithubRateLimitExceeded(String),
+
+    #[error("Package index not available in offline mode: {0:?}")]
+    OfflineIndexNotFound(String),
+
+    #[error("Release not available in offline mode: {0:?}")]
+    OfflineReleaseNotFound(String),
+
+    #[error("Artifact not available in offline mode: {0:?}")]
+    OfflineArtifactNotFound(String),
+
+    #[error("Not supported in offline mode: {0}")]
+    OfflineUnsupported(String),
 }
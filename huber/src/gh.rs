@@ -1,16 +1,32 @@
-use std::fs::remove_dir_all;
+use std::collections::HashMap;
+use std::fs::{remove_dir_all, rename, File};
 use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
 use git2::{Cred, ErrorCode, FetchOptions, RemoteCallbacks, Repository};
-use log::debug;
+use log::{debug, warn};
 use octocrab::auth::Auth;
-use octocrab::{Octocrab, OctocrabBuilder};
-
-use crate::fs::is_empty_dir;
-use crate::model::package::Package;
-use crate::model::release::Release;
+use octocrab::{models, Octocrab, OctocrabBuilder};
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use urlencoding::encode;
+
+use crate::error::HuberError::GithubRateLimitExceeded;
+use crate::fs::{dir, is_empty_dir};
+use crate::model::config::{Config, ConfigFieldConvertTrait, ConfigPath};
+use crate::model::package::{host_base_url, Package, PackageSource};
+use crate::model::release::{Release, ReleaseKind};
+
+// number of repositories queried by a GraphQL request
+const GRAPHQL_BATCH_SIZE: usize = 50;
 
 #[async_trait]
 pub trait GithubClientTrait {
@@ -44,30 +60,249 @@ pub trait GithubClientTrait {
 pub struct GithubClient {
     octocrab: Octocrab,
     ssh_key: Option<PathBuf>,
+    base_uri: String,
+    cache: Option<ReleaseMetadataCache>,
 }
 
 unsafe impl Send for GithubClient {}
 
 unsafe impl Sync for GithubClient {}
 
+// GitHub API response of release metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ReleaseMetadataCacheEntry {
+    url: String,
+    etag: Option<String>,
+    cached_at: String,
+    body: String,
+}
+
+/// Cache of the release metadata fetched from GitHub API.
+///
+/// Responses are stored as `<sha256 of URL>.yaml`, and revalidated by the ETag (`If-None-Match`) after the TTL,
+/// which doesn't count against the rate limit if the response is not modified.
+struct ReleaseMetadataCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ReleaseMetadataCache {
+    fn get(&self, url: &str) -> Option<ReleaseMetadataCacheEntry> {
+        let file = File::open(self.entry_path(url)).ok()?;
+        serde_yaml::from_reader(file)
+            .ok()
+            .filter(|it: &ReleaseMetadataCacheEntry| it.url == url)
+    }
+
+    fn put(&self, entry: &ReleaseMetadataCacheEntry) -> anyhow::Result<()> {
+        let path = self.entry_path(&entry.url);
+        let temp_path = path.with_extension(format!("{}.tmp", process::id()));
+
+        serde_yaml::to_writer(File::create(&temp_path)?, entry)?;
+        rename(&temp_path, &path)?;
+
+        Ok(())
+    }
+
+    fn is_fresh(&self, entry: &ReleaseMetadataCacheEntry) -> bool {
+        DateTime::parse_from_rfc3339(&entry.cached_at)
+            .ok()
+            .and_then(|it| (Utc::now() - it.with_timezone(&Utc)).to_std().ok())
+            .is_some_and(|it| it < self.ttl)
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let key = format!("{:x}", Sha256::digest(url.as_bytes()));
+        self.dir.join(key).with_extension("yaml")
+    }
+}
+
 impl GithubClient {
-    pub fn new(auth: Auth, ssh_key: Option<PathBuf>) -> Self {
-        Self {
-            octocrab: {
-                let builder = OctocrabBuilder::default();
-                match auth {
-                    Auth::PersonalToken(token) => builder.personal_token(token),
-                    Auth::UserAccessToken(token) => builder.user_access_token(token),
-                    _ => {
-                        debug!("Use github client with no auth");
-                        builder
-                    }
-                }
-                .build()
-                .expect("Failed to build GitHub client")
-            },
+    pub fn new(
+        auth: Auth,
+        ssh_key: Option<PathBuf>,
+        base_uri: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let mut builder = OctocrabBuilder::default();
+        if let Some(uri) = &base_uri {
+            debug!("Use github client with base URI {}", uri);
+            builder = builder
+                .base_uri(uri.as_str())
+                .map_err(|e| anyhow!("Invalid GitHub base URI {}: {}", uri, e))?;
+        }
+
+        let octocrab = match auth {
+            Auth::PersonalToken(token) => builder.personal_token(token),
+            Auth::UserAccessToken(token) => builder.user_access_token(token),
+            _ => {
+                debug!("Use github client with no auth");
+                builder
+            }
+        }
+        .build()?;
+
+        Ok(Self {
+            base_uri: base_uri.unwrap_or("https://api.github.com".to_string()),
+            octocrab,
             ssh_key,
+            cache: None,
+        })
+    }
+
+    /// Cache the release metadata in the directory, and revalidate it after the TTL
+    pub fn with_cache(mut self, dir: PathBuf, ttl: Duration) -> Self {
+        self.cache = Some(ReleaseMetadataCache { dir, ttl });
+        self
+    }
+
+    /// Create the client for the package source with the credential selected per host and owner,
+    /// GitHub Enterprise packages use the API of their own host instead of the configured one
+    pub fn from_source(config: &Config, source: &PackageSource) -> anyhow::Result<Self> {
+        let auth = config
+            .to_source_credential(source)
+            .map_or(Auth::None, |it| Auth::PersonalToken(it.token.into()));
+        let base_uri = match source {
+            PackageSource::GithubEnterprise { host, .. } => {
+                Some(format!("{}/api/v3", host_base_url(host)))
+            }
+            _ => config.github_base_uri.clone(),
+        };
+
+        let client = Self::new(auth, config.to_github_key_path(), base_uri)?;
+        let cache_config = &config.release_metadata_cache;
+        Ok(match config.release_metadata_cache_dir() {
+            Ok(dir) if cache_config.enabled => {
+                client.with_cache(dir, Duration::from_secs(cache_config.ttl_secs))
+            }
+            _ => client,
+        })
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, route: &str) -> anyhow::Result<T> {
+        let url = format!("{}{}", self.base_uri.trim_end_matches('/'), route);
+        let cached_entry = self.cache.as_ref().and_then(|it| it.get(&url));
+
+        if let Some(entry) = &cached_entry {
+            if self.cache.as_ref().is_some_and(|it| it.is_fresh(entry)) {
+                debug!("Using the cached response of {}", url);
+                return Ok(serde_json::from_str(&entry.body)?);
+            }
+        }
+
+        let mut headers = HeaderMap::new();
+        if let Some(etag) = cached_entry.as_ref().and_then(|it| it.etag.as_ref()) {
+            headers.insert(IF_NONE_MATCH, HeaderValue::from_str(etag)?);
+        }
+
+        let response = self
+            .octocrab
+            ._get_with_headers(route, Some(headers))
+            .await?;
+        let status = response.status();
+
+        if let Err(e) = check_rate_limit(&url, status, response.headers()) {
+            if let Some(entry) = cached_entry {
+                warn!("{}, using the stale cached response of {}", e, url);
+                return Ok(serde_json::from_str(&entry.body)?);
+            }
+            return Err(e);
+        }
+
+        let entry = match cached_entry {
+            Some(entry) if status == StatusCode::NOT_MODIFIED => {
+                debug!("Revalidated the cached response of {}", url);
+                entry
+            }
+            _ => {
+                let response = octocrab::map_github_error(response).await?;
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|it| it.to_str().ok())
+                    .map(|it| it.to_string());
+
+                ReleaseMetadataCacheEntry {
+                    url: url.clone(),
+                    etag,
+                    cached_at: String::new(),
+                    body: self.octocrab.body_to_string(response).await?,
+                }
+            }
+        };
+
+        let value = serde_json::from_str(&entry.body)?;
+        if let Some(cache) = &self.cache {
+            let entry = ReleaseMetadataCacheEntry {
+                cached_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                ..entry
+            };
+            if let Err(e) = dir(cache.dir.clone()).and_then(|_| cache.put(&entry)) {
+                debug!("Failed to cache the response of {}: {}", url, e);
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Get the latest releases of the GitHub packages in batches by GraphQL API, which requires a token.
+    /// The latest release is preceded by the newest release if it's a different one (e.g. a newer pre-release),
+    /// so the releases are the newest first. The packages without a latest release are not included
+    pub async fn get_latest_releases(
+        &self,
+        pkgs: &[Package],
+    ) -> anyhow::Result<HashMap<String, Vec<Release>>> {
+        let mut releases = HashMap::new();
+
+        for pkgs in pkgs.chunks(GRAPHQL_BATCH_SIZE) {
+            let mut query = String::new();
+            for (i, pkg) in pkgs.iter().enumerate() {
+                let PackageSource::Github { owner, repo } = &pkg.source else {
+                    continue;
+                };
+                query.push_str(&format!(
+                    "r{}: repository(owner: {}, name: {}) {{ \
+                    latestRelease {{ tagName isPrerelease isDraft }} \
+                    releases(first: 1, orderBy: {{ field: CREATED_AT, direction: DESC }}) \
+                    {{ nodes {{ tagName isPrerelease isDraft }} }} }} ",
+                    i,
+                    serde_json::to_string(owner)?,
+                    serde_json::to_string(repo)?
+                ));
+            }
+            if query.is_empty() {
+                continue;
+            }
+
+            debug!("Getting the latest releases of {} packages", pkgs.len());
+            let response: serde_json::Value = self
+                .octocrab
+                .graphql(&json!({ "query": format!("query {{ {}}}", query) }))
+                .await?;
+            if let Some(errors) = response.get("errors") {
+                debug!("Partial errors of getting the latest releases: {}", errors);
+            }
+
+            for (i, pkg) in pkgs.iter().enumerate() {
+                let repository = &response["data"][format!("r{}", i)];
+                let Some(latest_release) = to_graphql_release(pkg, &repository["latestRelease"])
+                else {
+                    continue;
+                };
+
+                let mut pkg_releases = vec![];
+                if let Some(newest_release) =
+                    to_graphql_release(pkg, &repository["releases"]["nodes"][0])
+                        .filter(|it| it.version != latest_release.version)
+                {
+                    pkg_releases.push(newest_release);
+                }
+                pkg_releases.push(latest_release);
+
+                releases.insert(pkg.name.clone(), pkg_releases);
+            }
         }
+
+        Ok(releases)
     }
 
     fn clone_fresh<P: AsRef<Path> + Send>(&self, url: &str, dir: P) -> anyhow::Result<Repository> {
@@ -162,6 +397,41 @@ impl GithubClient {
     }
 }
 
+// log the remaining rate limit, and fail clearly if it's exhausted
+fn check_rate_limit(url: &str, status: StatusCode, headers: &HeaderMap) -> anyhow::Result<()> {
+    let header = |name: &str| headers.get(name).and_then(|it| it.to_str().ok());
+
+    let (Some(remaining), Some(limit)) =
+        (header("x-ratelimit-remaining"), header("x-ratelimit-limit"))
+    else {
+        return Ok(());
+    };
+    debug!(
+        "GitHub API rate limit remaining: {}/{} ({})",
+        remaining, limit, url
+    );
+
+    if remaining == "0"
+        && matches!(
+            status,
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+        )
+    {
+        let reset = header("x-ratelimit-reset")
+            .and_then(|it| it.parse::<i64>().ok())
+            .and_then(|it| DateTime::from_timestamp(it, 0))
+            .map(|it| {
+                it.with_timezone(&Local)
+                    .to_rfc3339_opts(SecondsFormat::Secs, true)
+            })
+            .unwrap_or("unknown".to_string());
+
+        return Err(anyhow!(GithubRateLimitExceeded(reset)));
+    }
+
+    Ok(())
+}
+
 #[async_trait]
 impl GithubClientTrait for GithubClient {
     async fn get_latest_release(
@@ -172,14 +442,12 @@ impl GithubClientTrait for GithubClient {
     ) -> anyhow::Result<Release> {
         debug!("Getting the latest release of package {}", &pkg);
 
-        let release = if pkg.target()?.tag_version_regex_template.is_none() {
-            self.octocrab
-                .repos(owner, repo)
-                .releases()
-                .get_latest()
+        let release: models::repos::Release = if pkg.target()?.tag_version_regex_template.is_none()
+        {
+            self.get_json(&format!("/repos/{}/{}/releases/latest", owner, repo))
                 .await?
         } else {
-            self.octocrab.repos(owner, repo).releases().list().send().await?.into_iter().find(|it| {
+            self.get_json::<Vec<models::repos::Release>>(&format!("/repos/{}/{}/releases", owner, repo)).await?.into_iter().find(|it| {
                 pkg.parse_version_from_tag_name(&it.tag_name).is_ok()
             }).ok_or(anyhow!("Failed to find the matched latest version based on tag_version_regex_template {:?}", pkg))?
         };
@@ -204,11 +472,13 @@ impl GithubClientTrait for GithubClient {
     ) -> anyhow::Result<Release> {
         debug!("Getting the specific release of package {}/{}", &pkg, tag);
 
-        let release = self
-            .octocrab
-            .repos(owner, repo)
-            .releases()
-            .get_by_tag(tag)
+        let release: models::repos::Release = self
+            .get_json(&format!(
+                "/repos/{}/{}/releases/tags/{}",
+                owner,
+                repo,
+                encode(tag)
+            ))
             .await?;
         let mut release = Release::from(release);
 
@@ -229,12 +499,8 @@ impl GithubClientTrait for GithubClient {
     ) -> anyhow::Result<Vec<Release>> {
         debug!("Getting all releases of package {}", &pkg);
 
-        let releases = self
-            .octocrab
-            .repos(owner, repo)
-            .releases()
-            .list()
-            .send()
+        let releases: Vec<models::repos::Release> = self
+            .get_json(&format!("/repos/{}/{}/releases", owner, repo))
             .await?;
         let releases = releases
             .into_iter()
@@ -279,3 +545,197 @@ impl GithubClientTrait for GithubClient {
         Ok(())
     }
 }
+
+// the release of the GraphQL release node, which only contains the version and the kind
+fn to_graphql_release(pkg: &Package, node: &serde_json::Value) -> Option<Release> {
+    let tag_name = node["tagName"].as_str()?;
+
+    let kind = if node["isDraft"].as_bool().unwrap_or_default() {
+        ReleaseKind::Draft
+    } else if node["isPrerelease"].as_bool().unwrap_or_default() {
+        ReleaseKind::PreRelease
+    } else {
+        ReleaseKind::Release
+    };
+
+    Some(Release {
+        name: pkg.name.clone(),
+        version: tag_name.to_string(),
+        current: false,
+        package: Package {
+            version: Some(tag_name.to_string()),
+            detail: None,
+            release_kind: Some(kind),
+            ..pkg.clone()
+        },
+        executables: None,
+        kind: Some(kind),
+        assets: None,
+        env: None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use mockito::Matcher;
+
+    use super::*;
+    use crate::error::HuberError;
+
+    const RELEASE: &str = r#"{
+  "url": "https://api.github.com/repos/owner/tool/releases/1",
+  "html_url": "https://github.com/owner/tool/releases/tag/v1.0.0",
+  "assets_url": "https://api.github.com/repos/owner/tool/releases/1/assets",
+  "upload_url": "https://uploads.github.com/repos/owner/tool/releases/1/assets{?name,label}",
+  "id": 1,
+  "node_id": "RE_1",
+  "tag_name": "v1.0.0",
+  "target_commitish": "main",
+  "draft": false,
+  "prerelease": false,
+  "assets": []
+}"#;
+
+    fn package() -> Package {
+        Package {
+            name: "tool".to_string(),
+            source: PackageSource::Github {
+                owner: "owner".to_string(),
+                repo: "tool".to_string(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_release_cached() {
+        let mut server = mockito::Server::new_async().await;
+        let dir = tempfile::tempdir().unwrap();
+        let mock = server
+            .mock("GET", "/repos/owner/tool/releases/tags/v1.0.0")
+            .match_header("If-None-Match", Matcher::Missing)
+            .with_header("content-type", "application/json")
+            .with_header("etag", "\"v1\"")
+            .with_body(RELEASE)
+            .expect(1)
+            .create_async()
+            .await;
+        let not_modified_mock = server
+            .mock("GET", "/repos/owner/tool/releases/tags/v1.0.0")
+            .match_header("If-None-Match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = GithubClient::new(Auth::None, None, Some(server.url()))
+            .unwrap()
+            .with_cache(dir.path().to_path_buf(), Duration::from_secs(600));
+        for _ in 0..2 {
+            let release = client
+                .get_release("owner", "tool", "v1.0.0", &package())
+                .await
+                .unwrap();
+            assert_eq!(release.version, "v1.0.0");
+        }
+
+        // revalidated after the TTL
+        let client = GithubClient::new(Auth::None, None, Some(server.url()))
+            .unwrap()
+            .with_cache(dir.path().to_path_buf(), Duration::ZERO);
+        let release = client
+            .get_release("owner", "tool", "v1.0.0", &package())
+            .await
+            .unwrap();
+        assert_eq!(release.version, "v1.0.0");
+
+        mock.assert_async().await;
+        not_modified_mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_new_invalid_base_uri() {
+        assert!(GithubClient::new(Auth::None, None, Some("not a uri".to_string())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_exceeded() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/repos/owner/tool/releases/latest")
+            .with_status(403)
+            .with_header("content-type", "application/json")
+            .with_header("x-ratelimit-limit", "60")
+            .with_header("x-ratelimit-remaining", "0")
+            .with_header("x-ratelimit-reset", "1700000000")
+            .with_body(r#"{"message": "API rate limit exceeded"}"#)
+            .create_async()
+            .await;
+
+        let client = GithubClient::new(Auth::None, None, Some(server.url())).unwrap();
+        let err = client
+            .get_latest_release("owner", "tool", &package())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<HuberError>(),
+            Some(HuberError::GithubRateLimitExceeded(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_releases() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/graphql")
+            .match_header("Authorization", "Bearer token")
+            .match_body(Matcher::Regex(
+                r#"r0: repository\(owner: \\"owner\\", name: \\"tool\\"\)"#.to_string(),
+            ))
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{
+  "data": {
+    "r0": {
+      "latestRelease": { "tagName": "v1.0.0", "isPrerelease": false, "isDraft": false },
+      "releases": { "nodes": [{ "tagName": "v1.1.0-rc.1", "isPrerelease": true, "isDraft": false }] }
+    },
+    "r1": null
+  },
+  "errors": [{ "type": "NOT_FOUND", "message": "Could not resolve to a Repository" }]
+}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let missing_pkg = Package {
+            name: "missing".to_string(),
+            source: PackageSource::Github {
+                owner: "owner".to_string(),
+                repo: "missing".to_string(),
+            },
+            ..Default::default()
+        };
+        let client = GithubClient::new(
+            Auth::PersonalToken("token".to_string().into()),
+            None,
+            Some(server.url()),
+        )
+        .unwrap();
+        let releases = client
+            .get_latest_releases(&[package(), missing_pkg])
+            .await
+            .unwrap();
+
+        assert_eq!(releases.len(), 1);
+        let releases = &releases["tool"];
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].version, "v1.1.0-rc.1");
+        assert!(matches!(releases[0].kind, Some(ReleaseKind::PreRelease)));
+        assert_eq!(releases[1].version, "v1.0.0");
+        assert_eq!(releases[1].package.version.as_deref(), Some("v1.0.0"));
+        assert!(matches!(releases[1].kind, Some(ReleaseKind::Release)));
+        mock.assert_async().await;
+    }
+}
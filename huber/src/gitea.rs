@@ -0,0 +1,354 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use urlencoding::encode;
+
+use crate::credential::Credential;
+use crate::model::package::{
+    host_base_url, GithubAsset, GithubPackage, Package, PackageDetailType,
+};
+use crate::model::release::{Release, ReleaseKind};
+
+#[async_trait]
+pub trait GiteaClientTrait {
+    async fn get_latest_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        pkg: &Package,
+    ) -> anyhow::Result<Release>;
+    async fn get_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        pkg: &Package,
+    ) -> anyhow::Result<Release>;
+    async fn get_releases(
+        &self,
+        owner: &str,
+        repo: &str,
+        pkg: &Package,
+    ) -> anyhow::Result<Vec<Release>>;
+}
+
+pub struct GiteaClient {
+    client: reqwest::Client,
+    base_url: String,
+    credential: Option<Credential>,
+}
+
+unsafe impl Send for GiteaClient {}
+
+unsafe impl Sync for GiteaClient {}
+
+// the release of Gitea and Forgejo is compatible with the GitHub one, except some missing fields
+#[derive(Debug, Deserialize)]
+struct GiteaRelease {
+    id: u64,
+    tag_name: String,
+    #[serde(default)]
+    target_commitish: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    url: String,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    tarball_url: String,
+    #[serde(default)]
+    zipball_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    published_at: String,
+    #[serde(default)]
+    assets: Vec<GiteaAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaAsset {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    download_count: u64,
+    #[serde(default)]
+    created_at: String,
+    browser_download_url: String,
+}
+
+impl GiteaClient {
+    pub fn new(host: &str, credential: Option<Credential>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: host_base_url(host),
+            credential,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(
+        &self,
+        owner: &str,
+        repo: &str,
+        path: &str,
+    ) -> anyhow::Result<T> {
+        let url = format!(
+            "{}/api/v1/repos/{}/{}/{}",
+            self.base_url,
+            encode(owner),
+            encode(repo),
+            path
+        );
+        debug!("Requesting Gitea API {}", url);
+
+        let mut request = self.client.get(&url);
+        if let Some(credential) = &self.credential {
+            request = credential.authorize(request);
+        }
+
+        Ok(request.send().await?.error_for_status()?.json().await?)
+    }
+
+    fn to_release(r: GiteaRelease, pkg: &Package) -> Release {
+        let release_kind = if r.draft {
+            ReleaseKind::Draft
+        } else if r.prerelease {
+            ReleaseKind::PreRelease
+        } else {
+            ReleaseKind::Release
+        };
+
+        Release {
+            name: pkg.name.clone(),
+            version: r.tag_name.clone(),
+            current: false,
+            package: Package {
+                name: pkg.name.clone(),
+                version: Some(r.tag_name.clone()),
+                description: pkg.description.clone(),
+                source: pkg.source.clone(),
+                targets: pkg.targets.clone(),
+                detail: Some(PackageDetailType::Github {
+                    package: GithubPackage {
+                        url: r.url,
+                        html_url: r.html_url,
+                        tarball_url: r.tarball_url,
+                        zipball_url: r.zipball_url,
+                        id: r.id,
+                        tag_name: r.tag_name,
+                        target_commitish: r.target_commitish,
+                        name: r.name,
+                        draft: r.draft,
+                        prerelease: r.prerelease,
+                        created_at: r.created_at,
+                        published_at: r.published_at,
+                        assets: r
+                            .assets
+                            .into_iter()
+                            .map(|it| GithubAsset {
+                                url: it.browser_download_url.clone(),
+                                browser_download_url: it.browser_download_url,
+                                id: it.id,
+                                name: it.name,
+                                size: it.size,
+                                download_count: it.download_count,
+                                created_at: it.created_at,
+                                ..Default::default()
+                            })
+                            .collect(),
+                        ..Default::default()
+                    },
+                }),
+                release_kind: Some(release_kind),
+                channel: pkg.channel.clone(),
+            },
+            executables: None,
+            kind: Some(release_kind),
+            assets: None,
+            env: None,
+        }
+    }
+}
+
+#[async_trait]
+impl GiteaClientTrait for GiteaClient {
+    async fn get_latest_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        pkg: &Package,
+    ) -> anyhow::Result<Release> {
+        debug!("Getting the latest release of package {}", &pkg);
+
+        let release = if pkg.target()?.tag_version_regex_template.is_none() {
+            self.get(owner, repo, "releases/latest").await?
+        } else {
+            self.get::<Vec<GiteaRelease>>(owner, repo, "releases")
+                .await?
+                .into_iter()
+                .find(|it| pkg.parse_version_from_tag_name(&it.tag_name).is_ok())
+                .ok_or(anyhow!("Failed to find the matched latest version based on tag_version_regex_template {:?}", pkg))?
+        };
+
+        Ok(Self::to_release(release, pkg))
+    }
+
+    async fn get_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        tag: &str,
+        pkg: &Package,
+    ) -> anyhow::Result<Release> {
+        debug!("Getting the specific release of package {}/{}", &pkg, tag);
+
+        let release = self
+            .get(owner, repo, &format!("releases/tags/{}", encode(tag)))
+            .await?;
+
+        Ok(Self::to_release(release, pkg))
+    }
+
+    async fn get_releases(
+        &self,
+        owner: &str,
+        repo: &str,
+        pkg: &Package,
+    ) -> anyhow::Result<Vec<Release>> {
+        debug!("Getting all releases of package {}", &pkg);
+
+        let releases: Vec<GiteaRelease> = self.get(owner, repo, "releases").await?;
+
+        Ok(releases
+            .into_iter()
+            .map(|it| Self::to_release(it, pkg))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::package::PackageSource;
+
+    const RELEASES: &str = r#"[
+  {
+    "id": 2,
+    "tag_name": "v1.1.0-rc.1",
+    "name": "v1.1.0-rc.1",
+    "draft": false,
+    "prerelease": true,
+    "assets": []
+  },
+  {
+    "id": 1,
+    "tag_name": "v1.0.0",
+    "name": "v1.0.0",
+    "draft": false,
+    "prerelease": false,
+    "published_at": "2024-01-01T00:00:00Z",
+    "assets": [
+      {
+        "id": 1,
+        "name": "tool-linux-amd64.tar.gz",
+        "size": 1024,
+        "download_count": 10,
+        "created_at": "2024-01-01T00:00:00Z",
+        "uuid": "0b4b4c4f-0e0b-4b4c-8f0e-0b4b4c4f0e0b",
+        "browser_download_url": "https://codeberg.org/owner/tool/releases/download/v1.0.0/tool-linux-amd64.tar.gz"
+      }
+    ]
+  }
+]"#;
+
+    fn package(host: &str) -> Package {
+        Package {
+            name: "tool".to_string(),
+            source: PackageSource::Gitea {
+                host: host.to_string(),
+                owner: "owner".to_string(),
+                repo: "tool".to_string(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_releases() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/repos/owner/tool/releases")
+            .match_header("Authorization", "Bearer token")
+            .with_header("content-type", "application/json")
+            .with_body(RELEASES)
+            .create_async()
+            .await;
+
+        let pkg = package(&server.url());
+        let client = GiteaClient::new(&server.url(), Some(Credential::new("token")));
+        let releases = client.get_releases("owner", "tool", &pkg).await.unwrap();
+        mock.assert_async().await;
+
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].kind, Some(ReleaseKind::PreRelease));
+        assert_eq!(releases[1].version, "v1.0.0");
+        assert_eq!(releases[1].kind, Some(ReleaseKind::Release));
+
+        let Some(PackageDetailType::Github { package }) = &releases[1].package.detail else {
+            panic!("unexpected release detail");
+        };
+        assert_eq!(package.assets[0].name, "tool-linux-amd64.tar.gz");
+        assert_eq!(
+            package.assets[0].browser_download_url,
+            "https://codeberg.org/owner/tool/releases/download/v1.0.0/tool-linux-amd64.tar.gz"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release() {
+        let mut server = mockito::Server::new_async().await;
+        let release = serde_json::to_string(
+            &serde_json::from_str::<Vec<serde_json::Value>>(RELEASES).unwrap()[1],
+        )
+        .unwrap();
+        server
+            .mock("GET", "/api/v1/repos/owner/tool/releases/latest")
+            .with_body(&release)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v1/repos/owner/tool/releases/tags/v1.0.0")
+            .with_body(&release)
+            .create_async()
+            .await;
+
+        let pkg = package(&server.url());
+        let client = GiteaClient::new(&server.url(), None);
+
+        let latest = client
+            .get_latest_release("owner", "tool", &pkg)
+            .await
+            .unwrap();
+        assert_eq!(latest.version, "v1.0.0");
+
+        let release = client
+            .get_release("owner", "tool", "v1.0.0", &pkg)
+            .await
+            .unwrap();
+        assert_eq!(release.version, "v1.0.0");
+
+        assert!(client
+            .get_release("owner", "tool", "v2.0.0", &pkg)
+            .await
+            .is_err());
+    }
+}
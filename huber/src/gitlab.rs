@@ -0,0 +1,349 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::debug;
+use semver::Version;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use urlencoding::encode;
+
+use crate::credential::Credential;
+use crate::model::package::{
+    host_base_url, GitlabAssetLink, GitlabPackage, Package, PackageDetailType,
+};
+use crate::model::release::{Release, ReleaseKind};
+
+// the maximum number of items per page allowed by GitLab API
+const PER_PAGE: usize = 100;
+
+#[async_trait]
+pub trait GitlabClientTrait {
+    async fn get_latest_release(&self, project: &str, pkg: &Package) -> anyhow::Result<Release>;
+    async fn get_release(&self, project: &str, tag: &str, pkg: &Package)
+        -> anyhow::Result<Release>;
+    async fn get_releases(&self, project: &str, pkg: &Package) -> anyhow::Result<Vec<Release>>;
+}
+
+pub struct GitlabClient {
+    client: reqwest::Client,
+    base_url: String,
+    credential: Option<Credential>,
+}
+
+unsafe impl Send for GitlabClient {}
+
+unsafe impl Sync for GitlabClient {}
+
+#[derive(Debug, Deserialize)]
+struct GitlabRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    released_at: Option<String>,
+    #[serde(default)]
+    upcoming_release: bool,
+    #[serde(default)]
+    assets: GitlabReleaseAssets,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct GitlabReleaseAssets {
+    #[serde(default)]
+    links: Vec<GitlabAssetLink>,
+}
+
+impl GitlabClient {
+    pub fn new(host: &str, credential: Option<Credential>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: host_base_url(host),
+            credential,
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, project: &str, path: &str) -> anyhow::Result<T> {
+        Ok(self.request(project, path).await?.json().await?)
+    }
+
+    // get the items of all pages, following the next page in the x-next-page header
+    async fn get_all<T: DeserializeOwned>(
+        &self,
+        project: &str,
+        path: &str,
+    ) -> anyhow::Result<Vec<T>> {
+        let mut items = vec![];
+        let mut page = "1".to_string();
+
+        loop {
+            let response = self
+                .request(
+                    project,
+                    &format!("{}?per_page={}&page={}", path, PER_PAGE, page),
+                )
+                .await?;
+            let next_page = response
+                .headers()
+                .get("x-next-page")
+                .and_then(|it| it.to_str().ok())
+                .filter(|it| !it.is_empty())
+                .map(String::from);
+            items.extend(response.json::<Vec<T>>().await?);
+
+            match next_page {
+                Some(next_page) => page = next_page,
+                None => return Ok(items),
+            }
+        }
+    }
+
+    async fn request(&self, project: &str, path: &str) -> anyhow::Result<reqwest::Response> {
+        let url = format!(
+            "{}/api/v4/projects/{}/{}",
+            self.base_url,
+            encode(project),
+            path
+        );
+        debug!("Requesting GitLab API {}", url);
+
+        let mut request = self.client.get(&url);
+        if let Some(credential) = &self.credential {
+            request = request.header("PRIVATE-TOKEN", &credential.token);
+        }
+
+        Ok(request.send().await?.error_for_status()?)
+    }
+
+    // upcoming releases and semantic pre-release versions are regarded as pre-releases
+    fn release_kind(r: &GitlabRelease) -> ReleaseKind {
+        match Version::parse(r.tag_name.trim_start_matches("v")) {
+            _ if r.upcoming_release => ReleaseKind::PreRelease,
+            Ok(v) if !v.pre.is_empty() => ReleaseKind::PreRelease,
+            _ => ReleaseKind::Release,
+        }
+    }
+
+    fn to_release(r: GitlabRelease, pkg: &Package) -> Release {
+        let release_kind = Self::release_kind(&r);
+
+        Release {
+            name: pkg.name.clone(),
+            version: r.tag_name.clone(),
+            current: false,
+            package: Package {
+                name: pkg.name.clone(),
+                version: Some(r.tag_name.clone()),
+                description: pkg.description.clone(),
+                source: pkg.source.clone(),
+                targets: pkg.targets.clone(),
+                detail: Some(PackageDetailType::Gitlab {
+                    package: GitlabPackage {
+                        tag_name: r.tag_name,
+                        name: r.name.unwrap_or_default(),
+                        created_at: r.created_at.unwrap_or_default(),
+                        released_at: r.released_at.unwrap_or_default(),
+                        upcoming_release: r.upcoming_release,
+                        assets: r.assets.links,
+                    },
+                }),
+                release_kind: Some(release_kind),
+                channel: pkg.channel.clone(),
+            },
+            executables: None,
+            kind: Some(release_kind),
+            assets: None,
+            env: None,
+        }
+    }
+}
+
+#[async_trait]
+impl GitlabClientTrait for GitlabClient {
+    async fn get_latest_release(&self, project: &str, pkg: &Package) -> anyhow::Result<Release> {
+        debug!("Getting the latest release of package {}", &pkg);
+
+        let release = if pkg.target()?.tag_version_regex_template.is_none() {
+            // the latest permalink may refer to an upcoming release or a pre-release
+            match self
+                .get::<GitlabRelease>(project, "releases/permalink/latest")
+                .await?
+            {
+                it if Self::release_kind(&it) == ReleaseKind::Release => it,
+                _ => self
+                    .get_all::<GitlabRelease>(project, "releases")
+                    .await?
+                    .into_iter()
+                    .find(|it| Self::release_kind(it) == ReleaseKind::Release)
+                    .ok_or(anyhow!("Failed to find the latest release of {}", pkg))?,
+            }
+        } else {
+            self.get_all::<GitlabRelease>(project, "releases")
+                .await?
+                .into_iter()
+                .find(|it| pkg.parse_version_from_tag_name(&it.tag_name).is_ok())
+                .ok_or(anyhow!("Failed to find the matched latest version based on tag_version_regex_template {:?}", pkg))?
+        };
+
+        Ok(Self::to_release(release, pkg))
+    }
+
+    async fn get_release(
+        &self,
+        project: &str,
+        tag: &str,
+        pkg: &Package,
+    ) -> anyhow::Result<Release> {
+        debug!("Getting the specific release of package {}/{}", &pkg, tag);
+
+        let release = self
+            .get(project, &format!("releases/{}", encode(tag)))
+            .await?;
+
+        Ok(Self::to_release(release, pkg))
+    }
+
+    async fn get_releases(&self, project: &str, pkg: &Package) -> anyhow::Result<Vec<Release>> {
+        debug!("Getting all releases of package {}", &pkg);
+
+        let releases: Vec<GitlabRelease> = self.get_all(project, "releases").await?;
+
+        Ok(releases
+            .into_iter()
+            .map(|it| Self::to_release(it, pkg))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use mockito::Matcher;
+
+    use super::*;
+    use crate::model::package::PackageSource;
+
+    const RELEASES: &str = r#"[
+  {
+    "tag_name": "v1.1.0-rc.1",
+    "name": "v1.1.0-rc.1",
+    "released_at": "2024-02-01T00:00:00Z",
+    "upcoming_release": false,
+    "assets": {"links": []}
+  },
+  {
+    "tag_name": "v1.0.0",
+    "name": "v1.0.0",
+    "released_at": "2024-01-01T00:00:00Z",
+    "upcoming_release": false,
+    "assets": {
+      "links": [
+        {
+          "id": 1,
+          "name": "tool-linux-amd64.tar.gz",
+          "url": "https://example.com/tool-linux-amd64.tar.gz",
+          "direct_asset_url": "https://gitlab.example.com/group/tool/-/releases/v1.0.0/downloads/tool-linux-amd64.tar.gz",
+          "link_type": "package"
+        }
+      ]
+    }
+  }
+]"#;
+
+    fn package(host: &str) -> Package {
+        Package {
+            name: "tool".to_string(),
+            source: PackageSource::Gitlab {
+                host: host.to_string(),
+                project: "group/tool".to_string(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_releases() {
+        let mut server = mockito::Server::new_async().await;
+        let releases: Vec<serde_json::Value> = serde_json::from_str(RELEASES).unwrap();
+        let first_page_mock = server
+            .mock("GET", "/api/v4/projects/group%2Ftool/releases")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("per_page".to_string(), PER_PAGE.to_string()),
+                Matcher::UrlEncoded("page".to_string(), "1".to_string()),
+            ]))
+            .match_header("PRIVATE-TOKEN", "token")
+            .with_header("content-type", "application/json")
+            .with_header("x-next-page", "2")
+            .with_body(serde_json::to_string(&releases[..1]).unwrap())
+            .create_async()
+            .await;
+        let last_page_mock = server
+            .mock("GET", "/api/v4/projects/group%2Ftool/releases")
+            .match_query(Matcher::UrlEncoded("page".to_string(), "2".to_string()))
+            .match_header("PRIVATE-TOKEN", "token")
+            .with_header("content-type", "application/json")
+            .with_header("x-next-page", "")
+            .with_body(serde_json::to_string(&releases[1..]).unwrap())
+            .create_async()
+            .await;
+
+        let pkg = package(&server.url());
+        let client = GitlabClient::new(&server.url(), Some(Credential::new("token")));
+        let releases = client.get_releases("group/tool", &pkg).await.unwrap();
+        first_page_mock.assert_async().await;
+        last_page_mock.assert_async().await;
+
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].kind, Some(ReleaseKind::PreRelease));
+        assert_eq!(releases[1].version, "v1.0.0");
+        assert_eq!(releases[1].kind, Some(ReleaseKind::Release));
+
+        let Some(PackageDetailType::Gitlab { package }) = &releases[1].package.detail else {
+            panic!("unexpected release detail");
+        };
+        assert_eq!(package.assets[0].name, "tool-linux-amd64.tar.gz");
+    }
+
+    #[tokio::test]
+    async fn test_get_latest_release() {
+        let mut server = mockito::Server::new_async().await;
+        let releases = serde_json::from_str::<Vec<serde_json::Value>>(RELEASES).unwrap();
+        let release = serde_json::to_string(&releases[1]).unwrap();
+        // the pre-release is skipped
+        server
+            .mock(
+                "GET",
+                "/api/v4/projects/group%2Ftool/releases/permalink/latest",
+            )
+            .with_body(serde_json::to_string(&releases[0]).unwrap())
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Ftool/releases")
+            .match_query(Matcher::Any)
+            .with_body(RELEASES)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/api/v4/projects/group%2Ftool/releases/v1.0.0")
+            .with_body(&release)
+            .create_async()
+            .await;
+
+        let pkg = package(&server.url());
+        let client = GitlabClient::new(&server.url(), None);
+
+        let latest = client.get_latest_release("group/tool", &pkg).await.unwrap();
+        assert_eq!(latest.version, "v1.0.0");
+
+        let release = client
+            .get_release("group/tool", "v1.0.0", &pkg)
+            .await
+            .unwrap();
+        assert_eq!(release.version, "v1.0.0");
+
+        assert!(client
+            .get_release("group/tool", "v2.0.0", &pkg)
+            .await
+            .is_err());
+    }
+}
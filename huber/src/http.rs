@@ -0,0 +1,235 @@
+use anyhow::anyhow;
+use async_trait::async_trait;
+use log::debug;
+use regex::Regex;
+use semver::Version;
+use serde_json::Value;
+
+use crate::credential::find_url_credential;
+use crate::model::config::HostCredential;
+use crate::model::package::{HttpPackage, HttpVersionSource, Package, PackageDetailType};
+use crate::model::release::{Release, ReleaseKind};
+
+#[async_trait]
+pub trait HttpClientTrait {
+    async fn get_latest_release(
+        &self,
+        versions: &HttpVersionSource,
+        pkg: &Package,
+    ) -> anyhow::Result<Release>;
+    async fn get_release(&self, version: &str, pkg: &Package) -> anyhow::Result<Release>;
+    async fn get_releases(
+        &self,
+        versions: &HttpVersionSource,
+        pkg: &Package,
+    ) -> anyhow::Result<Vec<Release>>;
+}
+
+#[derive(Default)]
+pub struct HttpClient {
+    credentials: Vec<HostCredential>,
+}
+
+unsafe impl Send for HttpClient {}
+
+unsafe impl Sync for HttpClient {}
+
+impl HttpClient {
+    pub fn new(credentials: Vec<HostCredential>) -> Self {
+        Self { credentials }
+    }
+
+    async fn get_content(&self, url: &str) -> anyhow::Result<String> {
+        let mut request = reqwest::Client::new().get(url);
+        if let Some(credential) = find_url_credential(&self.credentials, url) {
+            request = credential.authorize(request);
+        }
+
+        Ok(request.send().await?.error_for_status()?.text().await?)
+    }
+
+    async fn get_versions(&self, versions: &HttpVersionSource) -> anyhow::Result<Vec<String>> {
+        match versions {
+            HttpVersionSource::Json { url, path } => {
+                debug!("Getting versions from JSON index {}", url);
+                let content = self.get_content(url).await?;
+                parse_json_versions(&content, path)
+            }
+            HttpVersionSource::Html { url, regex } => {
+                debug!("Getting versions from HTML listing {}", url);
+                let content = self.get_content(url).await?;
+                parse_html_versions(&content, regex)
+            }
+            HttpVersionSource::Static { versions } => Ok(versions.clone()),
+        }
+    }
+
+    fn new_release(version: &str, pkg: &Package) -> Release {
+        let kind = match Version::parse(version.trim_start_matches("v")) {
+            Ok(v) if !v.pre.is_empty() => ReleaseKind::PreRelease,
+            _ => ReleaseKind::Release,
+        };
+
+        Release {
+            name: pkg.name.clone(),
+            version: version.to_string(),
+            current: false,
+            package: Package {
+                name: pkg.name.clone(),
+                version: Some(version.to_string()),
+                description: pkg.description.clone(),
+                source: pkg.source.clone(),
+                targets: pkg.targets.clone(),
+                detail: Some(PackageDetailType::Http {
+                    package: HttpPackage {
+                        version: version.to_string(),
+                    },
+                }),
+                release_kind: Some(kind),
+                channel: pkg.channel.clone(),
+            },
+            executables: None,
+            kind: Some(kind),
+            assets: None,
+            env: None,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpClientTrait for HttpClient {
+    async fn get_latest_release(
+        &self,
+        versions: &HttpVersionSource,
+        pkg: &Package,
+    ) -> anyhow::Result<Release> {
+        debug!("Getting the latest release of package {}", &pkg);
+
+        self.get_releases(versions, pkg)
+            .await?
+            .into_iter()
+            .find(|it| it.kind == Some(ReleaseKind::Release))
+            .ok_or_else(|| anyhow!("Failed to find the latest release of {}", pkg))
+    }
+
+    async fn get_release(&self, version: &str, pkg: &Package) -> anyhow::Result<Release> {
+        debug!(
+            "Getting the specific release of package {}/{}",
+            &pkg, version
+        );
+
+        Ok(Self::new_release(version, pkg))
+    }
+
+    async fn get_releases(
+        &self,
+        versions: &HttpVersionSource,
+        pkg: &Package,
+    ) -> anyhow::Result<Vec<Release>> {
+        debug!("Getting all releases of package {}", &pkg);
+
+        let mut versions = self.get_versions(versions).await?;
+        if pkg.target()?.tag_version_regex_template.is_some() {
+            versions.retain(|it| pkg.parse_version_from_tag_name(it).is_ok());
+        }
+
+        // the latest version first, and versions not in semver at the end
+        let semver = |version: &String| {
+            pkg.parse_version_from_tag_name(version)
+                .ok()
+                .and_then(|it| Version::parse(it.trim_start_matches("v")).ok())
+        };
+        versions.sort_by_key(|it| std::cmp::Reverse(semver(it)));
+
+        Ok(versions
+            .iter()
+            .map(|it| Self::new_release(it, pkg))
+            .collect())
+    }
+}
+
+/// Parse versions from the JSON document by the dot-separated path,
+/// `*` matches array items or object keys
+pub fn parse_json_versions(content: &str, path: &str) -> anyhow::Result<Vec<String>> {
+    let mut values: Vec<Value> = vec![serde_json::from_str(content)?];
+
+    for segment in path.split('.').filter(|it| !it.is_empty()) {
+        values = values
+            .into_iter()
+            .flat_map(|value| match (segment, value) {
+                ("*", Value::Array(items)) => items,
+                ("*", Value::Object(map)) => {
+                    map.into_iter().map(|(k, _)| Value::String(k)).collect()
+                }
+                (key, Value::Object(mut map)) => map.remove(key).into_iter().collect(),
+                _ => vec![],
+            })
+            .collect();
+    }
+
+    Ok(dedup(values.into_iter().filter_map(|it| match it {
+        Value::String(s) => Some(s),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })))
+}
+
+/// Parse versions from the HTML content by the regex, the first group is regarded as the version if any
+pub fn parse_html_versions(content: &str, regex: &str) -> anyhow::Result<Vec<String>> {
+    let regex = Regex::new(regex)?;
+
+    Ok(dedup(regex.captures_iter(content).filter_map(|it| {
+        it.get(1)
+            .or_else(|| it.get(0))
+            .map(|it| it.as_str().to_string())
+    })))
+}
+
+fn dedup(versions: impl Iterator<Item = String>) -> Vec<String> {
+    let mut results: Vec<String> = vec![];
+    for version in versions {
+        if !results.contains(&version) {
+            results.push(version);
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_versions() {
+        let content = r#"[{"version": "1.2.0"}, {"version": "1.1.0"}, {"name": "1.0.0"}]"#;
+        assert_eq!(
+            parse_json_versions(content, "*.version").unwrap(),
+            vec!["1.2.0", "1.1.0"]
+        );
+
+        let content = r#"{"versions": {"1.2.0": {}, "1.1.0": {}}}"#;
+        let mut versions = parse_json_versions(content, "versions.*").unwrap();
+        versions.sort();
+        assert_eq!(versions, vec!["1.1.0", "1.2.0"]);
+
+        assert!(parse_json_versions("invalid", "*").is_err());
+    }
+
+    #[test]
+    fn test_parse_html_versions() {
+        let content = r#"
+<a href="/terraform/1.2.0/">terraform_1.2.0</a>
+<a href="/terraform/1.2.0-rc1/">terraform_1.2.0-rc1</a>
+<a href="/terraform/1.1.0/">terraform_1.1.0</a>
+"#;
+        assert_eq!(
+            parse_html_versions(content, r#"href="/terraform/([^/]+)/""#).unwrap(),
+            vec!["1.2.0", "1.2.0-rc1", "1.1.0"]
+        );
+        assert_eq!(
+            parse_html_versions(content, r"terraform_\d+\.\d+\.\d+").unwrap(),
+            vec!["terraform_1.2.0", "terraform_1.1.0"]
+        );
+    }
+}
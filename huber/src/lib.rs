@@ -1,11 +1,21 @@
+mod artifact;
+mod checksum;
 pub mod cmd;
 mod compress;
+mod credential;
+mod download;
 pub mod error;
 pub mod fs;
 mod gh;
+mod gitea;
+mod gitlab;
+mod http;
 pub mod log;
+mod mirror;
 pub mod model;
 mod os;
 pub mod parse;
 mod semver;
 pub mod service;
+pub mod shim;
+mod signature;
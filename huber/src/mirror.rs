@@ -0,0 +1,304 @@
+use std::fs::{create_dir_all, File};
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::HuberError;
+use crate::fs::dir;
+use crate::model::config::{Config, ConfigPath};
+use crate::model::package::{Package, PackageDetailType};
+use crate::model::release::{Release, ReleaseKind, SortModelTrait};
+use crate::service::repo::list_repositories;
+
+// the package detail is not serialized along with the release, but required to download the assets
+#[derive(Serialize, Deserialize)]
+struct MirroredRelease {
+    release: Release,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<PackageDetailType>,
+}
+
+/// Local mirror of release metadata and artifacts, used to resolve and install packages in offline mode.
+///
+/// Releases are stored as `releases/<package>.yaml`, and artifacts as `artifacts/<host>/<URL path>`.
+pub struct Mirror {
+    dir: PathBuf,
+}
+
+impl Mirror {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Get the releases of the package, the newest first
+    pub fn get_releases(&self, name: &str) -> anyhow::Result<Vec<Release>> {
+        let path = self.releases_file(name);
+        if !path.exists() {
+            return Ok(vec![]);
+        }
+
+        let releases: Vec<MirroredRelease> = serde_yaml::from_reader(File::open(path)?)?;
+        Ok(releases
+            .into_iter()
+            .map(|it| {
+                let mut release = it.release;
+                release.package.detail = it.detail;
+                release
+            })
+            .collect())
+    }
+
+    /// Save the releases of the package, merged with the saved ones
+    pub fn save_releases(&self, name: &str, releases: &[Release]) -> anyhow::Result<()> {
+        let mut merged_releases = releases.to_vec();
+        for release in self.get_releases(name)? {
+            if !merged_releases
+                .iter()
+                .any(|it| it.version == release.version)
+            {
+                merged_releases.push(release);
+            }
+        }
+        merged_releases.sort_by_version();
+
+        let path = self.releases_file(name);
+        debug!("Saving releases of {} to {:?}", name, path);
+
+        dir(self.dir.join("releases"))?;
+        let merged_releases: Vec<_> = merged_releases
+            .into_iter()
+            .map(|it| MirroredRelease {
+                detail: it.package.detail.clone(),
+                release: it,
+            })
+            .collect();
+        serde_yaml::to_writer(File::create(path)?, &merged_releases)?;
+
+        Ok(())
+    }
+
+    pub fn get_artifact(&self, url: &str) -> Option<PathBuf> {
+        self.artifact_file(url).ok().filter(|it| it.is_file())
+    }
+
+    /// Get the path of the artifact of the URL, the parent directories are created
+    pub fn artifact_path(&self, url: &str) -> anyhow::Result<PathBuf> {
+        let path = self.artifact_file(url)?;
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent)?;
+        }
+
+        Ok(path)
+    }
+
+    fn artifact_file(&self, url: &str) -> anyhow::Result<PathBuf> {
+        let parsed_url = Url::parse(url)?;
+        let host = parsed_url
+            .host_str()
+            .ok_or(anyhow!("No host found in URL {}", url))?;
+        let host = match parsed_url.port() {
+            Some(port) => format!("{}_{}", host, port),
+            None => host.to_string(),
+        };
+
+        let mut path = self.dir.join("artifacts").join(host);
+        for segment in parsed_url.path_segments().into_iter().flatten() {
+            if !segment.is_empty() && segment != "." && segment != ".." {
+                path.push(segment);
+            }
+        }
+
+        Ok(path)
+    }
+
+    fn releases_file(&self, name: &str) -> PathBuf {
+        self.dir.join("releases").join(format!("{}.yaml", name))
+    }
+}
+
+/// Cache the releases fetched online, so they are resolvable in offline mode
+pub fn cache_releases(config: &Config, name: &str, releases: &[Release]) {
+    let result = config
+        .release_cache_dir()
+        .and_then(|it| Mirror::new(it).save_releases(name, releases));
+
+    if let Err(e) = result {
+        debug!("Failed to cache releases of {}: {}", name, e);
+    }
+}
+
+/// Find the releases of the package from the mirrors and the cached releases
+pub fn find_offline_releases(config: &Config, pkg: &Package) -> anyhow::Result<Vec<Release>> {
+    let mut releases: Vec<Release> = vec![];
+
+    for mirror in offline_mirrors(config)? {
+        for release in mirror.get_releases(&pkg.name)? {
+            if !releases.iter().any(|it| it.version == release.version) {
+                releases.push(release);
+            }
+        }
+    }
+    releases.sort_by_version();
+
+    Ok(releases)
+}
+
+/// Find the release of the version, or the latest release, from the mirrors and the cached releases
+pub fn find_offline_release(
+    config: &Config,
+    pkg: &Package,
+    version: Option<&str>,
+) -> anyhow::Result<Release> {
+    let releases = find_offline_releases(config, pkg)?;
+
+    let release = match version {
+        Some(version) => releases.iter().find(|it| {
+            it.version == version
+                || it.version.trim_start_matches('v') == version.trim_start_matches('v')
+        }),
+        None => match &pkg.channel {
+            Some(channel) => releases.iter().find(|it| channel.matches(it)),
+            None => releases
+                .iter()
+                .find(|it| matches!(it.kind, None | Some(ReleaseKind::Release)))
+                .or(releases.first()),
+        },
+    };
+
+    release.cloned().ok_or_else(|| {
+        let name = match version {
+            Some(version) => format!("{}@{}", pkg.name, version),
+            None => pkg.name.clone(),
+        };
+        anyhow!(HuberError::OfflineReleaseNotFound(name))
+    })
+}
+
+/// Find the artifact of the URL from the mirrors
+pub fn find_offline_artifact(config: &Config, url: &str) -> anyhow::Result<PathBuf> {
+    offline_mirrors(config)?
+        .iter()
+        .find_map(|it| it.get_artifact(url))
+        .ok_or_else(|| anyhow!(HuberError::OfflineArtifactNotFound(url.to_string())))
+}
+
+// the configured mirror directory, the directories of the repository files added from mirror bundles,
+// and the release cache in order
+fn offline_mirrors(config: &Config) -> anyhow::Result<Vec<Mirror>> {
+    let mut mirrors = vec![];
+    if let Some(mirror_dir) = &config.mirror_dir {
+        mirrors.push(Mirror::new(mirror_dir.clone()));
+    }
+    for repo in list_repositories(config)? {
+        if let Some(dir) = repo.file.as_ref().and_then(|it| it.parent()) {
+            mirrors.push(Mirror::new(dir.to_path_buf()));
+        }
+    }
+    mirrors.push(Mirror::new(config.release_cache_dir()?));
+
+    Ok(mirrors)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::package::HttpPackage;
+    use crate::model::release::{tool_release, ReleaseChannel};
+
+    #[test]
+    fn test_save_releases() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = Mirror::new(dir.path().to_path_buf());
+
+        assert!(mirror.get_releases("tool").unwrap().is_empty());
+
+        let mut latest_release = tool_release("v1.0.0", ReleaseKind::Release);
+        latest_release.package.detail = Some(PackageDetailType::Http {
+            package: HttpPackage {
+                version: "v1.0.0".to_string(),
+            },
+        });
+        mirror.save_releases("tool", &[latest_release]).unwrap();
+        mirror
+            .save_releases(
+                "tool",
+                &[
+                    tool_release("v1.1.0-rc.1", ReleaseKind::PreRelease),
+                    tool_release("v0.9.0", ReleaseKind::Release),
+                ],
+            )
+            .unwrap();
+
+        let releases = mirror.get_releases("tool").unwrap();
+        assert!(matches!(
+            releases[1].package.detail,
+            Some(PackageDetailType::Http { ref package }) if package.version == "v1.0.0"
+        ));
+
+        let versions: Vec<_> = releases.into_iter().map(|it| it.version).collect();
+        assert_eq!(versions, vec!["v1.1.0-rc.1", "v1.0.0", "v0.9.0"]);
+    }
+
+    #[test]
+    fn test_find_offline_release_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            huber_dir: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+        Mirror::new(config.release_cache_dir().unwrap())
+            .save_releases(
+                "tool",
+                &[
+                    tool_release("v1.2.0-nightly.20240101", ReleaseKind::PreRelease),
+                    tool_release("v1.1.0-rc.1", ReleaseKind::PreRelease),
+                    tool_release("v1.0.0", ReleaseKind::Release),
+                ],
+            )
+            .unwrap();
+
+        let mut pkg = Package {
+            name: "tool".to_string(),
+            ..Default::default()
+        };
+        let find_version =
+            |pkg: &Package| find_offline_release(&config, pkg, None).unwrap().version;
+
+        assert_eq!(find_version(&pkg), "v1.0.0");
+        pkg.channel = Some(ReleaseChannel::PreRelease);
+        assert_eq!(find_version(&pkg), "v1.2.0-nightly.20240101");
+        pkg.channel = Some(r"-rc\.\d+$".parse().unwrap());
+        assert_eq!(find_version(&pkg), "v1.1.0-rc.1");
+        pkg.channel = Some("beta-.*".parse().unwrap());
+        assert!(find_offline_release(&config, &pkg, None).is_err());
+    }
+
+    #[test]
+    fn test_artifact_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = Mirror::new(dir.path().to_path_buf());
+
+        let url = "https://github.com/owner/tool/releases/download/v1.0.0/tool.tar.gz";
+        assert_eq!(
+            mirror.artifact_file(url).unwrap(),
+            dir.path()
+                .join("artifacts/github.com/owner/tool/releases/download/v1.0.0/tool.tar.gz")
+        );
+        assert_eq!(
+            mirror
+                .artifact_file("http://localhost:8080/../tool.tar.gz")
+                .unwrap(),
+            dir.path().join("artifacts/localhost_8080/tool.tar.gz")
+        );
+
+        assert_eq!(mirror.get_artifact(url), None);
+        let path = mirror.artifact_file(url).unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "tool").unwrap();
+        assert!(mirror.get_artifact(url).is_some());
+    }
+}
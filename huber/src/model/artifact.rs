@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ArtifactCacheEntry {
+    pub url: String,
+    // SHA-256 of the artifact, which is also the name of the cached file
+    pub checksum: String,
+    pub size: u64,
+    pub last_used: String,
+}
@@ -2,14 +2,16 @@ use std::collections::HashMap;
 use std::env;
 use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use libcli_rs::output::OutputFormat;
 use log::LevelFilter;
 use octocrab::auth::Auth;
 use serde::{Deserialize, Serialize};
 
+use crate::credential::{find_credential, find_url_credential, Credential};
 use crate::fs::dir;
-use crate::model::package::Package;
+use crate::model::package::{host_name, Package, PackageSource};
 
 pub const HUBER_PKG_ROOT_DIR: &str = "HUBER_PKG_ROOT_DIR"; // generated directory
 pub const GENERATED_DIR_NAME: &str = "generated-v1";
@@ -23,6 +25,115 @@ pub struct Config {
     pub github_key: Option<String>,
     pub github_base_uri: Option<String>,
     pub lock_pkg_versions: HashMap<String, String>,
+    #[serde(default)]
+    pub gitlab_token: Option<String>,
+    #[serde(default)]
+    pub credentials: Vec<HostCredential>,
+    #[serde(default)]
+    pub download_retry: DownloadRetryPolicy,
+    #[serde(default)]
+    pub artifact_cache: ArtifactCacheConfig,
+    #[serde(default)]
+    pub release_metadata_cache: ReleaseMetadataCacheConfig,
+    // resolve and install packages only from the cached index, cached releases and the mirror directory
+    #[serde(default)]
+    pub offline: bool,
+    #[serde(default)]
+    pub mirror_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub shim: ShimConfig,
+    // packages whose installed versions are also linked in the bin directory with version suffixes (e.g. kubectl-1.29)
+    #[serde(default)]
+    pub versioned_executables: Vec<String>,
+}
+
+// credentials of a hosting instance (e.g. GitHub Enterprise Server, Gitea, GitLab) or a download server,
+// optionally limited to the owner (e.g. GitHub organization). The token is taken from token,
+// token_env (environment variable name) or netrc_file (netrc-style file) in order
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct HostCredential {
+    pub host: String,
+    pub owner: Option<String>,
+    pub token: Option<String>,
+    pub token_env: Option<String>,
+    pub netrc_file: Option<String>,
+}
+
+// retry policy of downloads, the backoff is doubled for each retry up to max_backoff_ms
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DownloadRetryPolicy {
+    pub retries: u32,
+    pub backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for DownloadRetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: 3,
+            backoff_ms: 1000,
+            max_backoff_ms: 30000,
+        }
+    }
+}
+
+impl DownloadRetryPolicy {
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .backoff_ms
+            .saturating_mul(2u64.saturating_pow(attempt))
+            .min(self.max_backoff_ms);
+
+        Duration::from_millis(backoff)
+    }
+}
+
+// persistent cache of downloaded artifacts shared across huber directories,
+// the least recently used artifacts are evicted if the total size exceeds max_size_mb
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ArtifactCacheConfig {
+    pub enabled: bool,
+    pub dir: Option<PathBuf>,
+    pub max_size_mb: u64,
+}
+
+impl Default for ArtifactCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: None,
+            max_size_mb: 2048,
+        }
+    }
+}
+
+// cache of the release metadata fetched from GitHub API, revalidated by ETag after ttl_secs
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ReleaseMetadataCacheConfig {
+    pub enabled: bool,
+    pub ttl_secs: u64,
+}
+
+impl Default for ReleaseMetadataCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            ttl_secs: 600,
+        }
+    }
+}
+
+// shims linked in the bin directory instead of the current executables, which run the versions required by
+// the nearest project version file (.huber-version or .tool-versions), or the current versions otherwise.
+// The required versions not installed are installed on demand if auto_install is enabled
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ShimConfig {
+    pub enabled: bool,
+    pub auto_install: bool,
 }
 
 impl Config {
@@ -64,8 +175,12 @@ pub trait ConfigPath {
 
     fn bin_dir(&self) -> anyhow::Result<PathBuf>;
     fn temp_dir(&self) -> anyhow::Result<PathBuf>;
+    fn artifact_cache_dir(&self) -> anyhow::Result<PathBuf>;
+    fn release_cache_dir(&self) -> anyhow::Result<PathBuf>;
+    fn release_metadata_cache_dir(&self) -> anyhow::Result<PathBuf>;
     fn repo_root_dir(&self) -> anyhow::Result<PathBuf>;
     fn huber_repo_dir(&self) -> anyhow::Result<PathBuf>;
+    fn metadata_dir(&self) -> anyhow::Result<PathBuf>;
 
     fn external_repo_dir(&self, name: &str) -> anyhow::Result<PathBuf>;
     fn external_repo_file(&self, name: &str) -> anyhow::Result<PathBuf>;
@@ -80,24 +195,20 @@ pub trait ConfigPath {
     fn installed_pkg_dir(&self, pkg: &Package, version: &str) -> anyhow::Result<PathBuf>;
     fn installed_pkg_bin_dir(&self, pkg: &Package, version: &str) -> anyhow::Result<PathBuf>;
     fn installed_pkg_manifest_file(&self, pkg: &Package, version: &str) -> anyhow::Result<PathBuf>;
+    fn installed_pkg_link_dir(&self, pkg: &Package, version: &str) -> anyhow::Result<PathBuf>;
+    fn staging_pkg_dir(&self, pkg: &Package, version: &str) -> anyhow::Result<PathBuf>;
 
     fn current_pkg_dir(&self, pkg: &Package) -> anyhow::Result<PathBuf>;
     fn current_pkg_bin_dir(&self, pkg: &Package) -> anyhow::Result<PathBuf>;
     fn current_pkg_manifest_file(&self, pkg: &Package) -> anyhow::Result<PathBuf>;
     fn current_index_file(&self) -> anyhow::Result<PathBuf>;
+    fn current_history_file(&self, pkg: &Package) -> anyhow::Result<PathBuf>;
+    fn shim_index_file(&self) -> anyhow::Result<PathBuf>;
 }
 
 impl Default for Config {
     fn default() -> Self {
-        let default_config = Self {
-            log_level: LevelFilter::Off.to_string(),
-            output_format: OutputFormat::Console,
-            huber_dir: dirs::home_dir().unwrap().join(".huber"),
-            github_token: None,
-            github_key: None,
-            github_base_uri: Some("https://api.github.com".to_string()),
-            lock_pkg_versions: Default::default(),
-        };
+        let default_config = Self::defaults(dirs::home_dir().unwrap().join(".huber"));
 
         let config_path = default_config.config_file().unwrap();
         if config_path.exists() {
@@ -108,6 +219,42 @@ impl Default for Config {
     }
 }
 
+impl Config {
+    /// Load the config of the huber directory, or the default config of it if not found
+    pub fn from_huber_dir(huber_dir: &Path) -> anyhow::Result<Self> {
+        let mut config = Self::defaults(huber_dir.to_path_buf());
+
+        let config_path = config.config_file()?;
+        if config_path.exists() {
+            config = serde_yaml::from_reader(File::open(config_path)?)?;
+            config.huber_dir = huber_dir.to_path_buf();
+        }
+
+        Ok(config)
+    }
+
+    fn defaults(huber_dir: PathBuf) -> Self {
+        Self {
+            log_level: LevelFilter::Off.to_string(),
+            output_format: OutputFormat::Console,
+            huber_dir,
+            github_token: None,
+            github_key: None,
+            github_base_uri: Some("https://api.github.com".to_string()),
+            lock_pkg_versions: Default::default(),
+            gitlab_token: None,
+            credentials: vec![],
+            download_retry: Default::default(),
+            artifact_cache: Default::default(),
+            release_metadata_cache: Default::default(),
+            offline: false,
+            mirror_dir: None,
+            shim: Default::default(),
+            versioned_executables: vec![],
+        }
+    }
+}
+
 impl From<PathBuf> for Config {
     fn from(p: PathBuf) -> Self {
         let f = File::open(&p).unwrap();
@@ -118,6 +265,8 @@ impl From<PathBuf> for Config {
 pub trait ConfigFieldConvertTrait {
     fn to_github_credentials(&self) -> Auth;
     fn to_github_key_path(&self) -> Option<PathBuf>;
+    fn to_source_credential(&self, source: &PackageSource) -> Option<Credential>;
+    fn to_download_credential(&self, source: &PackageSource, url: &str) -> Option<Credential>;
 }
 
 impl ConfigFieldConvertTrait for Config {
@@ -132,6 +281,32 @@ impl ConfigFieldConvertTrait for Config {
     fn to_github_key_path(&self) -> Option<PathBuf> {
         self.github_key.clone().map(PathBuf::from)
     }
+
+    fn to_source_credential(&self, source: &PackageSource) -> Option<Credential> {
+        let owner = source.owner();
+
+        find_credential(&self.credentials, &source.host(), Some(&owner)).or_else(|| {
+            match source {
+                PackageSource::Github { .. } => self.github_token.as_deref(),
+                PackageSource::Gitlab { .. } => self.gitlab_token.as_deref(),
+                _ => None,
+            }
+            .map(Credential::new)
+        })
+    }
+
+    fn to_download_credential(&self, source: &PackageSource, url: &str) -> Option<Credential> {
+        if let Some(credential) = find_url_credential(&self.credentials, url) {
+            return Some(credential);
+        }
+
+        // the artifacts released on the package source host share the credential of the source
+        if host_name(url) == source.host() {
+            self.to_source_credential(source)
+        } else {
+            None
+        }
+    }
 }
 
 impl ConfigPath for Config {
@@ -155,6 +330,24 @@ impl ConfigPath for Config {
         dir(env::temp_dir().join("huber"))
     }
 
+    fn artifact_cache_dir(&self) -> anyhow::Result<PathBuf> {
+        match &self.artifact_cache.dir {
+            Some(path) => dir(path.clone()),
+            None => dir(dirs::cache_dir()
+                .unwrap_or_else(env::temp_dir)
+                .join("huber")
+                .join("artifacts")),
+        }
+    }
+
+    fn release_cache_dir(&self) -> anyhow::Result<PathBuf> {
+        dir(self.huber_dir.join("cache"))
+    }
+
+    fn release_metadata_cache_dir(&self) -> anyhow::Result<PathBuf> {
+        dir(self.release_cache_dir()?.join("github"))
+    }
+
     fn repo_root_dir(&self) -> anyhow::Result<PathBuf> {
         dir(self.huber_dir.join("repos"))
     }
@@ -163,6 +356,10 @@ impl ConfigPath for Config {
         dir(self.repo_root_dir()?.join("huber"))
     }
 
+    fn metadata_dir(&self) -> anyhow::Result<PathBuf> {
+        dir(self.huber_dir.join("metadata"))
+    }
+
     fn external_repo_dir(&self, name: &str) -> anyhow::Result<PathBuf> {
         dir(self.repo_root_dir()?.join(name))
     }
@@ -229,6 +426,25 @@ impl ConfigPath for Config {
             .with_extension("yaml"))
     }
 
+    fn installed_pkg_link_dir(&self, pkg: &Package, version: &str) -> anyhow::Result<PathBuf> {
+        let version = pkg.parse_version_from_tag_name(&version.to_string())?;
+
+        // executables linked by the names in the bin directory, to run the version without switching the current one
+        Ok(self.installed_pkg_dir(pkg, &version)?.join(".huber-bin"))
+    }
+
+    fn staging_pkg_dir(&self, pkg: &Package, version: &str) -> anyhow::Result<PathBuf> {
+        let version = pkg.parse_version_from_tag_name(&version.to_string())?;
+
+        // under the huber directory, so it can be renamed to the installed package directory atomically
+        dir(self
+            .huber_dir
+            .join("staging")
+            .join(pkg.source.to_string())
+            .join(format!("{}_{}", pkg.source.owner(), pkg.name))
+            .join(version))
+    }
+
     fn current_pkg_dir(&self, pkg: &Package) -> anyhow::Result<PathBuf> {
         Ok(self.installed_pkg_base_dir(pkg)?.join("current"))
     }
@@ -250,4 +466,85 @@ impl ConfigPath for Config {
             .join("index")
             .with_extension("yaml"))
     }
+
+    fn current_history_file(&self, pkg: &Package) -> anyhow::Result<PathBuf> {
+        // out of the installed package directory, which only contains the installed versions
+        let history_dir = dir(self
+            .metadata_dir()?
+            .join("history")
+            .join(pkg.source.to_string()))?;
+
+        Ok(history_dir.join(format!("{}_{}.yaml", pkg.source.owner(), pkg.name).replace("/", "_")))
+    }
+
+    fn shim_index_file(&self) -> anyhow::Result<PathBuf> {
+        Ok(self
+            .installed_pkg_root_dir()?
+            .join("shims")
+            .with_extension("yaml"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::release::{tool_release, ReleaseKind};
+
+    #[test]
+    fn test_partial_config_blocks() {
+        let config: DownloadRetryPolicy = serde_yaml::from_str("retries: 5").unwrap();
+        assert_eq!(config.retries, 5);
+        assert_eq!(config.backoff_ms, 1000);
+
+        let config: ArtifactCacheConfig = serde_yaml::from_str("max_size_mb: 512").unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.max_size_mb, 512);
+
+        let config: ReleaseMetadataCacheConfig = serde_yaml::from_str("ttl_secs: 60").unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.ttl_secs, 60);
+
+        let config: ShimConfig = serde_yaml::from_str("enabled: true").unwrap();
+        assert!(config.enabled);
+        assert!(!config.auto_install);
+    }
+
+    #[test]
+    fn test_current_history_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::from_huber_dir(dir.path()).unwrap();
+        let pkg = tool_release("v1.0.0", ReleaseKind::Release).package;
+
+        let history_f = config.current_history_file(&pkg).unwrap();
+        assert_eq!(
+            history_f,
+            dir.path()
+                .join("metadata")
+                .join("history")
+                .join("github")
+                .join("owner_tool.yaml")
+        );
+        assert!(!history_f.starts_with(config.installed_pkg_base_dir(&pkg).unwrap()));
+    }
+
+    #[test]
+    fn test_from_huber_dir() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let config = Config::from_huber_dir(dir.path()).unwrap();
+        assert_eq!(config.huber_dir, dir.path());
+        assert!(!config.shim.enabled);
+
+        let mut config = Config::defaults(PathBuf::from("/other"));
+        config.shim.enabled = true;
+        serde_yaml::to_writer(
+            File::create(dir.path().join("config.yaml")).unwrap(),
+            &config,
+        )
+        .unwrap();
+
+        let config = Config::from_huber_dir(dir.path()).unwrap();
+        assert_eq!(config.huber_dir, dir.path());
+        assert!(config.shim.enabled);
+    }
 }
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub enum DoctorProblemKind {
+    // executable link in the bin directory pointing to a removed file
+    DanglingExecutable,
+    // current index entry without a readable current release manifest or link
+    InvalidCurrentRelease,
+    // installed version directory without a readable release manifest
+    OrphanVersionDir,
+    // installed package not found in any repository
+    PackageNotFound,
+    BinDirNotInPath,
+}
+
+// inconsistency of the installed state found by the doctor command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorProblem {
+    pub kind: DoctorProblemKind,
+    // package name or path
+    pub name: String,
+    pub detail: String,
+    pub fixed: bool,
+}
+
+impl DoctorProblem {
+    pub fn new(kind: DoctorProblemKind, name: &str, detail: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            detail: detail.to_string(),
+            fixed: false,
+        }
+    }
+}
@@ -1,4 +1,7 @@
+pub mod artifact;
 pub mod config;
+pub mod doctor;
 pub mod package;
+pub mod project;
 pub mod release;
 pub mod repo;
@@ -1,5 +1,5 @@
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -9,8 +9,9 @@ use anyhow::anyhow;
 use regex::Regex;
 use semver::Version;
 use serde::{Deserialize, Serialize};
+use url::Url;
 
-use crate::model::release::{ReleaseKind, SortModelTrait};
+use crate::model::release::{ReleaseChannel, ReleaseEnv, ReleaseKind, SortModelTrait};
 use crate::semver::VersionCompareTrait;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +38,11 @@ pub struct Package {
     #[serde(skip)]
     #[serde(with = "serde_yaml::with::singleton_map")]
     pub release_kind: Option<ReleaseKind>,
+
+    // release channel selected when installing, persisted in the release manifest
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ReleaseChannel>,
 }
 
 impl Default for Package {
@@ -52,6 +58,7 @@ impl Default for Package {
             targets: default_targets(),
             detail: None,
             release_kind: None,
+            channel: None,
         }
     }
 }
@@ -71,12 +78,52 @@ pub struct PackageSummary {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PackageSource {
-    Github { owner: String, repo: String },
+    Github {
+        owner: String,
+        repo: String,
+    },
+    // host is the GitHub Enterprise Server instance (e.g. github.example.com)
+    GithubEnterprise {
+        host: String,
+        owner: String,
+        repo: String,
+    },
+    // host is the Gitea or Forgejo instance (e.g. codeberg.org, https://gitea.example.com)
+    Gitea {
+        host: String,
+        owner: String,
+        repo: String,
+    },
+    // host is the GitLab instance (e.g. gitlab.com, https://gitlab.example.com),
+    // and project is the full path of the project (e.g. group/subgroup/project)
+    Gitlab {
+        host: String,
+        project: String,
+    },
+    // artifacts released on a plain download server.
+    // {version} can be used in url, and relative artifact names are resolved against it
+    Http {
+        url: String,
+        versions: HttpVersionSource,
+    },
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum HttpVersionSource {
+    // versions selected from a JSON document by a dot-separated path.
+    // * matches array items or object keys (e.g. *.version, versions.*)
+    Json { url: String, path: String },
+    // versions captured by the first group of the regex from an HTML directory listing
+    Html { url: String, regex: String },
+    Static { versions: Vec<String> },
+}
+
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PackageDetailType {
     Github { package: GithubPackage },
+    Gitlab { package: GitlabPackage },
+    Http { package: HttpPackage },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -104,6 +151,38 @@ pub struct PackageManagement {
     // only keep the {version} part
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scan_dirs: Option<Vec<String>>,
+
+    // checksum file of artifacts (e.g. checksums.txt, *.sha256, SHA256SUMS).
+    // {version}, {os}, {arch} can be used, and * matches any release asset name. Also, an external URL is acceptable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checksum_template: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PackageSignature>,
+
+    // environment variables of the installed package (e.g. GOROOT). {version}, {install_dir} can be used in values
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+
+    // extra directories added to PATH, relative to the installed package directory. {version} can be used
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_dirs: Option<Vec<String>>,
+}
+
+// {artifact}, {version}, {os}, {arch} can be used in signature_template. Also, an external URL is acceptable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PackageSignature {
+    // public_key is a PEM (or base64 encoded PEM) ECDSA P-256 public key.
+    // Keyless signatures (certificates or bundles) are not supported, and fail the verification
+    Cosign {
+        signature_template: String,
+        public_key: String,
+    },
+    // public_key is a minisign public key or the content of a minisign public key file
+    Minisign {
+        signature_template: String,
+        public_key: String,
+    },
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -130,6 +209,32 @@ pub struct GithubPackage {
     pub assets: Vec<GithubAsset>,
 }
 
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct GitlabPackage {
+    pub tag_name: String,
+    pub name: String,
+    pub created_at: String,
+    pub released_at: String,
+    pub upcoming_release: bool,
+    pub assets: Vec<GitlabAssetLink>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct GitlabAssetLink {
+    pub id: u64,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub direct_asset_url: Option<String>,
+    #[serde(default)]
+    pub link_type: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct HttpPackage {
+    pub version: String,
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct GithubAsset {
     pub url: String,
@@ -158,21 +263,87 @@ impl PackageSource {
             PackageSource::Github { owner, repo } => {
                 format!("https://github.com/{}/{}", owner, repo)
             }
+            PackageSource::GithubEnterprise { host, owner, repo }
+            | PackageSource::Gitea { host, owner, repo } => {
+                format!("{}/{}/{}", host_base_url(host), owner, repo)
+            }
+            PackageSource::Gitlab { host, project } => {
+                format!("{}/{}", host_base_url(host), project)
+            }
+            PackageSource::Http { url, .. } => url.split("{version}").next().unwrap().to_string(),
+        }
+    }
+
+    /// Get the host of the package source, which is used to select the credential
+    pub fn host(&self) -> String {
+        match self {
+            PackageSource::Github { .. } => "github.com".to_string(),
+            PackageSource::GithubEnterprise { host, .. }
+            | PackageSource::Gitea { host, .. }
+            | PackageSource::Gitlab { host, .. } => host_name(host),
+            PackageSource::Http { url, .. } => host_name(url),
         }
     }
 
     pub fn owner(&self) -> String {
         match self {
             PackageSource::Github { owner, repo: _ } => owner.to_string(),
+            PackageSource::GithubEnterprise { owner, .. } | PackageSource::Gitea { owner, .. } => {
+                owner.to_string()
+            }
+            PackageSource::Gitlab { project, .. } => project
+                .rsplit_once('/')
+                .map_or(project.as_str(), |(namespace, _)| namespace)
+                .to_string(),
+            PackageSource::Http { url, .. } => Url::parse(url)
+                .ok()
+                .and_then(|it| it.host_str().map(|it| it.to_string()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Resolve the artifact name against the download URL of the HTTP source
+    pub fn resolve_url(&self, name: &str, version: &str) -> Option<String> {
+        match self {
+            PackageSource::Github { .. }
+            | PackageSource::GithubEnterprise { .. }
+            | PackageSource::Gitea { .. }
+            | PackageSource::Gitlab { .. } => None,
+            PackageSource::Http { url, .. } => {
+                let mut url = url.replace("{version}", version.trim_start_matches("v"));
+                if !url.ends_with('/') {
+                    url.push('/');
+                }
+
+                Url::parse(&url)
+                    .and_then(|it| it.join(name))
+                    .ok()
+                    .map(|it| it.to_string())
+            }
+        }
+    }
+}
+
+impl PackageSignature {
+    pub fn signature_template(&self) -> &str {
+        match self {
+            PackageSignature::Cosign {
+                signature_template, ..
+            }
+            | PackageSignature::Minisign {
+                signature_template, ..
+            } => signature_template,
         }
     }
 }
 
 impl Package {
     pub fn target(&self) -> anyhow::Result<PackageManagement> {
-        let os = env::consts::OS;
-        let arch = env::consts::ARCH;
+        self.target_of(env::consts::OS, env::consts::ARCH)
+    }
 
+    /// Get the package management of the platform, `os` and `arch` are in the format of `env::consts`
+    pub fn target_of(&self, os: &str, arch: &str) -> anyhow::Result<PackageManagement> {
         let default_pkg_mgmt: Option<_> = self.targets.iter().find_map(|it| match it {
             PackageTargetType::Default(m) => Some(m.clone()),
             _ => None,
@@ -270,6 +441,41 @@ impl Package {
 
         Ok(scan_dirs)
     }
+
+    /// Resolve the environment of the installed release from the target, None if nothing declared
+    pub fn get_release_env(
+        &self,
+        version: &str,
+        install_dir: &Path,
+    ) -> anyhow::Result<Option<ReleaseEnv>> {
+        let target = self.target()?;
+        if target.env.is_none() && target.path_dirs.is_none() {
+            return Ok(None);
+        }
+
+        let version = version.trim_start_matches("v");
+        let install_dir_str = install_dir.to_string_lossy();
+
+        let vars = target
+            .env
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, value)| {
+                let value = value
+                    .replace("{version}", version)
+                    .replace("{install_dir}", &install_dir_str);
+                (name, value)
+            })
+            .collect();
+        let path_dirs = target
+            .path_dirs
+            .unwrap_or_default()
+            .into_iter()
+            .map(|it| install_dir.join(it.replace("{version}", version)))
+            .collect();
+
+        Ok(Some(ReleaseEnv { vars, path_dirs }))
+    }
 }
 
 impl From<octocrab::models::repos::Release> for GithubPackage {
@@ -323,6 +529,10 @@ impl Display for PackageSource {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             PackageSource::Github { .. } => write!(f, "github"),
+            PackageSource::GithubEnterprise { .. } => write!(f, "github-enterprise"),
+            PackageSource::Gitea { .. } => write!(f, "gitea"),
+            PackageSource::Gitlab { .. } => write!(f, "gitlab"),
+            PackageSource::Http { .. } => write!(f, "http"),
         }
     }
 }
@@ -364,6 +574,26 @@ impl SortModelTrait for Vec<PackageSummary> {
     }
 }
 
+/// Get the base URL of the hosting instance, HTTPS is used if the scheme is not specified
+pub fn host_base_url(host: &str) -> String {
+    if host.starts_with("http://") || host.starts_with("https://") {
+        host.trim_end_matches('/').to_string()
+    } else {
+        format!("https://{}", host.trim_end_matches('/'))
+    }
+}
+
+/// Get the host name (with the port if any) of the hosting instance or URL
+pub fn host_name(host: &str) -> String {
+    Url::parse(&host_base_url(host))
+        .ok()
+        .and_then(|it| {
+            it.host_str()
+                .map(|h| it.port().map_or(h.to_string(), |p| format!("{}:{}", h, p)))
+        })
+        .unwrap_or_else(|| host.to_string())
+}
+
 pub fn default_targets() -> Vec<PackageTargetType> {
     vec![
         PackageTargetType::LinuxAmd64(Default::default()),
@@ -412,3 +642,46 @@ pub fn default_targets_no_arm64_arm() -> Vec<PackageTargetType> {
         PackageTargetType::WindowsAmd64(Default::default()),
     ]
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_get_release_env() {
+        let mut pkg = Package {
+            name: "go".to_string(),
+            targets: vec![PackageTargetType::Default(Default::default())],
+            ..Default::default()
+        };
+        let install_dir = Path::new("/huber/packages/go/v1.22.0");
+        assert!(pkg
+            .get_release_env("v1.22.0", install_dir)
+            .unwrap()
+            .is_none());
+
+        pkg.targets = vec![PackageTargetType::Default(PackageManagement {
+            env: Some(BTreeMap::from([(
+                "GOROOT".to_string(),
+                "{install_dir}/go-{version}".to_string(),
+            )])),
+            path_dirs: Some(vec!["go-{version}/bin".to_string()]),
+            ..Default::default()
+        })];
+        let release_env = pkg
+            .get_release_env("v1.22.0", install_dir)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            release_env.vars,
+            BTreeMap::from([(
+                "GOROOT".to_string(),
+                "/huber/packages/go/v1.22.0/go-1.22.0".to_string()
+            )])
+        );
+        assert_eq!(
+            release_env.path_dirs,
+            vec![install_dir.join("go-1.22.0/bin")]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::release::ReleaseAsset;
+
+pub const PROJECT_MANIFEST_FILE: &str = "huber.yaml";
+pub const PROJECT_LOCK_FILE: &str = "huber.lock";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectManifest {
+    // package name -> version requirement (e.g. 'latest', '1.29.3', '~1.29' using Cargo's dependency version requirement format)
+    #[serde(default)]
+    pub packages: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ProjectLock {
+    #[serde(default)]
+    pub packages: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    // exact release tag
+    pub version: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+impl ProjectManifest {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let f = File::open(path)?;
+        Ok(serde_yaml::from_reader(f)?)
+    }
+}
+
+impl ProjectLock {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let f = File::open(path)?;
+        Ok(serde_yaml::from_reader(f)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let f = File::create(path)?;
+        Ok(serde_yaml::to_writer(f, self)?)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LockedPackage> {
+        self.packages.iter().find(|it| it.name == name)
+    }
+}
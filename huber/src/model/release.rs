@@ -1,8 +1,12 @@
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 use std::str::FromStr;
 
+use anyhow::anyhow;
+use regex::Regex;
 use semver::Version;
 use serde::{Deserialize, Serialize};
 
@@ -32,6 +36,24 @@ pub struct Release {
     pub package: Package,
     pub executables: Option<Vec<String>>,
     pub kind: Option<ReleaseKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub assets: Option<Vec<ReleaseAsset>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<ReleaseEnv>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ReleaseAsset {
+    pub url: String,
+    // SHA-256 checksum of the downloaded artifact
+    pub checksum: String,
+}
+
+// environment of the installed release resolved from the package target, exported by the env and run commands
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ReleaseEnv {
+    pub vars: BTreeMap<String, String>,
+    pub path_dirs: Vec<PathBuf>,
 }
 
 unsafe impl Send for Release {}
@@ -45,6 +67,95 @@ pub enum ReleaseKind {
     Release,
 }
 
+// release channel to resolve the latest version of a package, in the format of 'stable', 'prerelease'
+// or a regex of release tags (e.g. 'nightly-.*')
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(into = "String", try_from = "String")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    PreRelease,
+    Tag(String),
+}
+
+impl ReleaseChannel {
+    pub fn matches(&self, release: &Release) -> bool {
+        match self {
+            ReleaseChannel::Stable => matches!(release.kind, None | Some(ReleaseKind::Release)),
+            ReleaseChannel::PreRelease => release.kind != Some(ReleaseKind::Draft),
+            ReleaseChannel::Tag(regex) => {
+                release.kind != Some(ReleaseKind::Draft)
+                    && Regex::new(regex).is_ok_and(|it| it.is_match(&release.version))
+            }
+        }
+    }
+}
+
+impl FromStr for ReleaseChannel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stable" => Ok(ReleaseChannel::Stable),
+            "prerelease" | "pre-release" => Ok(ReleaseChannel::PreRelease),
+            _ => {
+                Regex::new(s).map_err(|e| anyhow!("Invalid release channel {}: {}", s, e))?;
+                Ok(ReleaseChannel::Tag(s.to_string()))
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for ReleaseChannel {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<ReleaseChannel> for String {
+    fn from(value: ReleaseChannel) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for ReleaseChannel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseChannel::Stable => write!(f, "stable"),
+            ReleaseChannel::PreRelease => write!(f, "prerelease"),
+            ReleaseChannel::Tag(regex) => write!(f, "{}", regex),
+        }
+    }
+}
+
+// previously current version of a package, recorded when switching the current version
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct ReleaseHistory {
+    pub version: String,
+    // RFC 3339 time of switching from the version
+    pub switched_at: String,
+}
+
+// executable linked as a shim in the bin directory, which resolves the version of the package to run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShimIndex {
+    pub executable: String,
+    pub package: Package,
+}
+
+// installed package compared with the latest released versions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutdatedRelease {
+    pub name: String,
+    pub current: String,
+    pub latest: Option<String>,
+    pub latest_prerelease: Option<String>,
+    pub lock: Option<String>,
+    pub outdated: bool,
+}
+
 impl Release {
     pub fn compare(&self, pkg: &Release) -> anyhow::Result<Ordering> {
         if Version::parse(&self.version).is_ok() {
@@ -112,9 +223,12 @@ impl From<octocrab::models::repos::Release> for Release {
                 version: Some(r.tag_name.clone()),
                 description: None,
                 release_kind: Some(release_kind),
+                channel: None,
             },
             executables: None,
             kind: Some(release_kind),
+            assets: None,
+            env: None,
         }
     }
 }
@@ -128,3 +242,73 @@ impl SortModelTrait for Vec<Release> {
         self.sort_by(|x, y| x.name.cmp(&y.name));
     }
 }
+
+// the release of the package 'tool' hosted on GitHub, shared by tests
+#[cfg(test)]
+pub fn tool_release(version: &str, kind: ReleaseKind) -> Release {
+    Release {
+        name: "tool".to_string(),
+        version: version.to_string(),
+        current: false,
+        package: Package {
+            name: "tool".to_string(),
+            source: PackageSource::Github {
+                owner: "owner".to_string(),
+                repo: "tool".to_string(),
+            },
+            targets: crate::model::package::default_targets(),
+            version: Some(version.to_string()),
+            ..Default::default()
+        },
+        executables: None,
+        kind: Some(kind),
+        assets: None,
+        env: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_release_channel_matches() {
+        let stable = tool_release("v1.0.0", ReleaseKind::Release);
+        let prerelease = tool_release("v1.1.0-rc.1", ReleaseKind::PreRelease);
+        let nightly = tool_release("nightly-20240101", ReleaseKind::PreRelease);
+        let draft = tool_release("nightly-20240102", ReleaseKind::Draft);
+
+        assert!(ReleaseChannel::Stable.matches(&stable));
+        assert!(!ReleaseChannel::Stable.matches(&prerelease));
+        assert!(ReleaseChannel::PreRelease.matches(&stable));
+        assert!(ReleaseChannel::PreRelease.matches(&prerelease));
+        assert!(!ReleaseChannel::PreRelease.matches(&draft));
+
+        let channel = ReleaseChannel::Tag("nightly-.*".to_string());
+        assert!(channel.matches(&nightly));
+        assert!(!channel.matches(&stable));
+        assert!(!channel.matches(&draft));
+    }
+
+    #[test]
+    fn test_release_channel_parse() {
+        assert_eq!(
+            "stable".parse::<ReleaseChannel>().unwrap(),
+            ReleaseChannel::Stable
+        );
+        assert_eq!(
+            "pre-release".parse::<ReleaseChannel>().unwrap(),
+            ReleaseChannel::PreRelease
+        );
+        assert_eq!(
+            "nightly-.*".parse::<ReleaseChannel>().unwrap(),
+            ReleaseChannel::Tag("nightly-.*".to_string())
+        );
+        assert!("nightly-(".parse::<ReleaseChannel>().is_err());
+
+        let yaml = serde_yaml::to_string(&ReleaseChannel::PreRelease).unwrap();
+        assert_eq!(yaml.trim(), "prerelease");
+        let channel: ReleaseChannel = serde_yaml::from_str("nightly-.*").unwrap();
+        assert_eq!(channel, ReleaseChannel::Tag("nightly-.*".to_string()));
+    }
+}
@@ -2,7 +2,9 @@ use anyhow::anyhow;
 use log::warn;
 use semver::{Version, VersionReq};
 
-/// Parse package name and version
+use crate::semver::parse_version_req;
+
+/// Parse package name and version, or version requirement
 ///
 /// # Examples
 ///
@@ -11,6 +13,10 @@ use semver::{Version, VersionReq};
 /// let (name, version) = parse_pkg_name_optional_semver("package-name@1.2.3").unwrap();
 /// assert_eq!(name, "package-name");
 /// assert_eq!(version, "1.2.3");
+///
+/// let (name, version) = parse_pkg_name_optional_semver("package-name@~1.2").unwrap();
+/// assert_eq!(name, "package-name");
+/// assert_eq!(version, "~1.2");
 /// ```
 pub fn parse_pkg_name_optional_semver(name_version: &str) -> anyhow::Result<(String, String)> {
     let result: Vec<_> = name_version.splitn(2, '@').collect();
@@ -20,6 +26,10 @@ pub fn parse_pkg_name_optional_semver(name_version: &str) -> anyhow::Result<(Str
     }
 
     let (name, version) = (result[0].to_string(), result[1].to_string());
+    if parse_version_req(&version).is_some() {
+        return Ok((name, version));
+    }
+
     if let Err(e) = Version::parse(version.trim_start_matches('v')) {
         warn!("Failed to parse semantic version ({}): {}", version, e);
     }
@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 use std::str::FromStr;
 
 use regex::Regex;
-use semver::Version;
+use semver::{Version, VersionReq};
 
 pub trait VersionCompareTrait {
     fn cmp_version(&self, version: &str) -> Option<Ordering>;
@@ -24,3 +24,110 @@ impl VersionCompareTrait for String {
             .partial_cmp(&comparing_version.unwrap())
     }
 }
+
+/// Parse the version requirement in Cargo's format (e.g. '~1.29', '^0.32', '>=1.2, <1.5', '1.*').
+/// Plain versions without an operator or a wildcard (e.g. '1.29.3', 'v1.29') are regarded as exact release tags.
+pub fn parse_version_req(version: &str) -> Option<VersionReq> {
+    let version = version.trim();
+    let is_req = version.starts_with(['~', '^', '=', '>', '<', '*'])
+        || version.split('.').any(|it| matches!(it, "*" | "x" | "X"));
+
+    if !is_req {
+        return None;
+    }
+
+    VersionReq::parse(version).ok()
+}
+
+/// Check if the version satisfies the requirement parsed by `parse_version_req`, or equals the exact release tag
+/// otherwise, the leading 'v' of versions is ignored. An empty or `latest` requirement is satisfied by any version
+pub fn is_version_satisfied(requirement: &str, version: &str) -> bool {
+    let requirement = requirement.trim();
+    let version = version.trim_start_matches('v');
+
+    if requirement.is_empty() || requirement == "latest" {
+        return true;
+    }
+
+    match parse_version_req(requirement) {
+        Some(requirement) => Version::parse(version).is_ok_and(|it| requirement.matches(&it)),
+        None => requirement.trim_start_matches('v') == version,
+    }
+}
+
+/// Find the highest version satisfying the requirement, the leading 'v' of versions is ignored
+pub fn find_max_satisfied_version<I>(requirement: &VersionReq, versions: I) -> Option<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    versions
+        .into_iter()
+        .filter_map(|it| {
+            Version::parse(it.trim_start_matches('v'))
+                .ok()
+                .filter(|version| requirement.matches(version))
+                .map(|version| (version, it))
+        })
+        .max_by(|(x, _), (y, _)| x.cmp(y))
+        .map(|(_, it)| it)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_req() {
+        assert!(parse_version_req("~1.29").is_some());
+        assert!(parse_version_req("^0.32").is_some());
+        assert!(parse_version_req(">=1.2, <1.5").is_some());
+        assert!(parse_version_req("1.*").is_some());
+        assert!(parse_version_req("1.29.3").is_none());
+        assert!(parse_version_req("v1.29").is_none());
+        assert!(parse_version_req("nightly").is_none());
+        assert!(parse_version_req("~nightly").is_none());
+    }
+
+    #[test]
+    fn test_is_version_satisfied() {
+        assert!(is_version_satisfied("", "v1.29.3"));
+        assert!(is_version_satisfied("latest", "v1.29.3"));
+        assert!(is_version_satisfied("1.29.3", "v1.29.3"));
+        assert!(is_version_satisfied("v1.29.3", "1.29.3"));
+        assert!(!is_version_satisfied("1.29.3", "v1.29.4"));
+        assert!(is_version_satisfied("1.29", "v1.29"));
+        assert!(!is_version_satisfied("1.29", "v1.29.4"));
+        assert!(is_version_satisfied("~1.29", "v1.29.4"));
+        assert!(!is_version_satisfied("~1.29", "v1.30.0"));
+        assert!(is_version_satisfied("^1", "1.30.0"));
+        assert!(!is_version_satisfied("~1.29", "nightly"));
+        assert!(is_version_satisfied("nightly", "nightly"));
+    }
+
+    #[test]
+    fn test_find_max_satisfied_version() {
+        let versions = vec![
+            "v1.28.5".to_string(),
+            "v1.29.3".to_string(),
+            "v1.29.10".to_string(),
+            "v1.30.0-rc.1".to_string(),
+            "v1.30.0".to_string(),
+            "nightly".to_string(),
+        ];
+
+        let requirement = parse_version_req("~1.29").unwrap();
+        assert_eq!(
+            find_max_satisfied_version(&requirement, versions.clone()),
+            Some("v1.29.10".to_string())
+        );
+
+        let requirement = parse_version_req("^1").unwrap();
+        assert_eq!(
+            find_max_satisfied_version(&requirement, versions.clone()),
+            Some("v1.30.0".to_string())
+        );
+
+        let requirement = parse_version_req("~2").unwrap();
+        assert_eq!(find_max_satisfied_version(&requirement, versions), None);
+    }
+}
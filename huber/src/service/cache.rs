@@ -12,12 +12,12 @@ use rayon::prelude::*;
 use regex::Regex;
 use simpledi_rs::di::{DIContainer, DIContainerExtTrait, DependencyInjectTrait};
 
-use crate::error::HuberError::PackageNotFound;
+use crate::error::HuberError::{OfflineIndexNotFound, PackageNotFound};
 use crate::gh::{GithubClient, GithubClientTrait};
 use crate::model::config::{Config, ConfigFieldConvertTrait, ConfigPath, HUBER_PKG_ROOT_DIR};
 use crate::model::package::{Package, PackageIndex};
 use crate::model::repo::{Repository, LOCAL_REPO};
-use crate::service::repo::{RepoAsyncTrait, RepoService, RepoTrait};
+use crate::service::repo::{list_repositories, RepoAsyncTrait, RepoService, RepoTrait};
 use crate::service::{ItemOperationTrait, ServiceTrait};
 
 lazy_static! {
@@ -246,7 +246,20 @@ impl CacheAsyncTrait for CacheService {
         let config = self.container.get::<Config>().unwrap();
 
         let path = env::var(HUBER_PKG_ROOT_DIR).unwrap_or_default();
-        if Path::new(&path).is_dir() {
+        if config.offline {
+            // packages of the added mirror bundles are still installable without the managed index
+            let index_file = config.pkg_index_file()?;
+            let has_bundle_repo = list_repositories(config)?
+                .iter()
+                .any(|it| it.file.is_some());
+            if !index_file.exists() && !has_bundle_repo {
+                return Err(anyhow!(OfflineIndexNotFound(
+                    index_file.to_string_lossy().to_string()
+                )));
+            }
+
+            debug!("Bypassed updating huber repo in offline mode");
+        } else if Path::new(&path).is_dir() {
             debug!(
                 "Bypassed updating repositories, because {} is set to {}",
                 HUBER_PKG_ROOT_DIR, path
@@ -256,15 +269,20 @@ impl CacheAsyncTrait for CacheService {
             let dir = config.huber_repo_dir()?;
 
             debug!("Updating {:?}", dir);
-            let client =
-                GithubClient::new(config.to_github_credentials(), config.to_github_key_path());
+            let client = GithubClient::new(
+                config.to_github_credentials(),
+                config.to_github_key_path(),
+                None,
+            )?;
             client.clone("innobead", "huber", dir).await?;
         }
 
         debug!("Updating external repos");
         let repo_service = self.container.get::<RepoService>().unwrap();
         for repo in repo_service.list()? {
-            if let Some(url) = repo.url {
+            if repo.url.is_some() && config.offline {
+                debug!("Bypassed updating repo {} in offline mode", repo.name);
+            } else if let Some(url) = repo.url {
                 debug!("Updating {:?}", config.external_repo_dir(&repo.name)?);
                 repo_service
                     .download_save_pkgs_file_from_remote_github(&repo.name, &url)
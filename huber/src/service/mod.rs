@@ -118,3 +118,20 @@ pub fn check_pkg_installed(
 
     Ok(())
 }
+
+/// Check the package has any installed versions, even if none of them is current, e.g. installed by `huber run`
+pub fn check_pkg_versions_installed(
+    pkg_service: &PackageService,
+    release_service: &ReleaseService,
+    pkg: &String,
+) -> anyhow::Result<()> {
+    if !pkg_service.has(pkg)? {
+        return Err(anyhow!("Package {} not found", pkg));
+    }
+
+    if !release_service.has_installed_versions(&pkg_service.get(pkg)?)? {
+        return Err(anyhow!("Package {} not installed", pkg));
+    }
+
+    Ok(())
+}
@@ -6,11 +6,10 @@ use log::debug;
 use simpledi_rs::di::{DIContainer, DIContainerExtTrait, DependencyInjectTrait};
 
 use crate::error::HuberError::PackageNotFound;
-use crate::gh::{GithubClient, GithubClientTrait};
-use crate::model::config::{Config, ConfigFieldConvertTrait};
-use crate::model::package::{Package, PackageSource, PackageSummary};
+use crate::model::package::{Package, PackageSummary};
 use crate::model::release::{ReleaseKind, SortModelTrait};
 use crate::service::cache::{CacheService, CacheTrait};
+use crate::service::release::ReleaseService;
 use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait, ItemSearchTrait, ServiceTrait};
 
 #[derive(Debug, Clone)]
@@ -111,25 +110,20 @@ impl ItemOperationAsyncTrait for PackageService {
     async fn find(&self, pkg_name: &Self::Condition_) -> anyhow::Result<Vec<Self::ItemInstance_>> {
         debug!("Finding packages: {}", pkg_name);
 
-        let config = self.container.get::<Config>().unwrap();
-        let client = GithubClient::new(config.to_github_credentials(), config.to_github_key_path());
+        let release_service = self.container.get::<ReleaseService>().unwrap();
         let pkg = self.get(pkg_name)?;
+        let releases = release_service.get_releases(&pkg).await?;
 
-        match &pkg.source {
-            PackageSource::Github { owner, repo } => {
-                let releases = client.get_releases(owner, repo, &pkg).await?;
-                Ok(releases
-                    .into_iter()
-                    .map(|it| {
-                        let mut pkg = it.package;
-                        pkg.version = Some(it.version);
-                        pkg.release_kind = it.kind;
-
-                        pkg
-                    })
-                    .collect())
-            }
-        }
+        Ok(releases
+            .into_iter()
+            .map(|it| {
+                let mut pkg = it.package;
+                pkg.version = Some(it.version);
+                pkg.release_kind = it.kind;
+
+                pkg
+            })
+            .collect())
     }
 }
 
@@ -1,36 +1,60 @@
 use std::collections::HashMap;
-use std::fs::{read_dir, read_link, remove_dir_all, remove_file, File};
-use std::io::Write;
-use std::path::PathBuf;
+use std::fs::{
+    copy, read_dir, read_link, read_to_string, remove_dir_all, remove_file, rename, File,
+};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::{env, fs};
+use std::{env, fs, slice};
 
 use anyhow::anyhow;
 use async_trait::async_trait;
+use chrono::{SecondsFormat, Utc};
 use filepath::FilePath;
 use fs_extra::move_items;
+use indicatif::MultiProgress;
 use is_executable::IsExecutable;
-use log::{debug, error, info};
+use libcli_rs::output::OutputFormat;
+use log::{debug, error, info, warn};
 use maplit::hashmap;
 use regex::Regex;
+use reqwest::header::{ACCEPT, USER_AGENT};
+use semver::Version;
 use simpledi_rs::di::{DIContainer, DIContainerExtTrait, DependencyInjectTrait};
 use symlink::{remove_symlink_dir, remove_symlink_file, symlink_dir, symlink_file};
 use url::Url;
 use urlencoding::decode;
 
+use crate::artifact::ArtifactCache;
+use crate::checksum::{parse_checksums, sha256_file, verify_checksum};
 use crate::cmd::PlatformStdLib;
 use crate::compress::uncompress_archive;
-use crate::fs::has_suffix;
+use crate::download::download_file;
+use crate::error::HuberError;
 use crate::fs::set_executable_permission;
+use crate::fs::{dir, has_suffix};
 use crate::gh::{GithubClient, GithubClientTrait};
+use crate::gitea::{GiteaClient, GiteaClientTrait};
+use crate::gitlab::{GitlabClient, GitlabClientTrait};
+use crate::http::{HttpClient, HttpClientTrait};
+use crate::mirror::{
+    cache_releases, find_offline_artifact, find_offline_release, find_offline_releases, Mirror,
+};
 use crate::model::config::{Config, ConfigFieldConvertTrait, ConfigPath};
-use crate::model::package::{GithubPackage, Package, PackageDetailType, PackageSource};
-use crate::model::release::{Release, ReleaseIndex};
+use crate::model::package::{
+    GithubPackage, GitlabPackage, HttpPackage, Package, PackageDetailType, PackageSource,
+};
+use crate::model::release::{
+    Release, ReleaseAsset, ReleaseChannel, ReleaseEnv, ReleaseHistory, ReleaseIndex, ShimIndex,
+    SortModelTrait,
+};
 use crate::os::{is_os_arch_match, trim_os_arch_version};
 use crate::service::package::PackageService;
 use crate::service::{ItemOperationAsyncTrait, ItemOperationTrait, ItemSearchTrait, ServiceTrait};
+use crate::signature::verify_signature;
 
 const SUPPORTED_ARCHIVE_TYPES: [&str; 6] = ["tar.gz", "tar.xz", "zip", "tar", "tgz", "gz"];
+// the number of previously current versions kept for rolling back
+const MAX_CURRENT_HISTORY: usize = 20;
 
 pub trait ReleaseTrait {
     fn current(&self, pkg: &Package) -> anyhow::Result<Release>;
@@ -54,7 +78,20 @@ pub trait ReleaseAsyncTrait {
         package: &Package,
         package_github: &GithubPackage,
         prefer_stdlib: &PlatformStdLib,
-    ) -> anyhow::Result<()>;
+    ) -> anyhow::Result<Vec<ReleaseAsset>>;
+
+    async fn download_install_gitlab_package(
+        &self,
+        package: &Package,
+        package_gitlab: &GitlabPackage,
+        prefer_stdlib: &PlatformStdLib,
+    ) -> anyhow::Result<Vec<ReleaseAsset>>;
+
+    async fn download_install_http_package(
+        &self,
+        package: &Package,
+        package_http: &HttpPackage,
+    ) -> anyhow::Result<Vec<ReleaseAsset>>;
 
     async fn set_current(&self, release: &mut Release) -> anyhow::Result<Vec<String>>;
 }
@@ -87,17 +124,212 @@ impl ReleaseService {
         Self { container: None }
     }
 
+    /// Get the latest release of the package in its release channel, the stable channel by default
     pub async fn get_latest(&self, pkg: &Package) -> anyhow::Result<Release> {
         debug!("Getting the latest release: {}", pkg);
 
         let config = self.container.get::<Config>().unwrap();
-        let client = GithubClient::new(config.to_github_credentials(), config.to_github_key_path());
 
-        match &pkg.source {
-            PackageSource::Github { owner, repo } => {
-                client.get_latest_release(owner, repo, pkg).await
+        let mut release = match &pkg.channel {
+            Some(channel) if *channel != ReleaseChannel::Stable => self
+                .get_releases(pkg)
+                .await?
+                .into_iter()
+                .find(|it| channel.matches(it))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No release found in the {} channel of package {}",
+                        channel,
+                        pkg.name
+                    )
+                })?,
+            _ if config.offline => find_offline_release(config, pkg, None)?,
+            _ => {
+                let release = match &pkg.source {
+                    PackageSource::Github { owner, repo }
+                    | PackageSource::GithubEnterprise { owner, repo, .. } => {
+                        GithubClient::from_source(config, &pkg.source)?
+                            .get_latest_release(owner, repo, pkg)
+                            .await?
+                    }
+                    PackageSource::Gitea { host, owner, repo } => {
+                        GiteaClient::new(host, config.to_source_credential(&pkg.source))
+                            .get_latest_release(owner, repo, pkg)
+                            .await?
+                    }
+                    PackageSource::Gitlab { host, project } => {
+                        GitlabClient::new(host, config.to_source_credential(&pkg.source))
+                            .get_latest_release(project, pkg)
+                            .await?
+                    }
+                    PackageSource::Http { versions, .. } => {
+                        HttpClient::new(config.credentials.clone())
+                            .get_latest_release(versions, pkg)
+                            .await?
+                    }
+                };
+                cache_releases(config, &pkg.name, slice::from_ref(&release));
+
+                release
+            }
+        };
+        release.package.channel = pkg.channel.clone();
+
+        Ok(release)
+    }
+
+    /// Get the releases of the package, the newest first
+    pub async fn get_releases(&self, pkg: &Package) -> anyhow::Result<Vec<Release>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let releases = match &pkg.source {
+            _ if config.offline => find_offline_releases(config, pkg)?,
+            PackageSource::Github { owner, repo }
+            | PackageSource::GithubEnterprise { owner, repo, .. } => {
+                GithubClient::from_source(config, &pkg.source)?
+                    .get_releases(owner, repo, pkg)
+                    .await?
+            }
+            PackageSource::Gitea { host, owner, repo } => {
+                GiteaClient::new(host, config.to_source_credential(&pkg.source))
+                    .get_releases(owner, repo, pkg)
+                    .await?
+            }
+            PackageSource::Gitlab { host, project } => {
+                GitlabClient::new(host, config.to_source_credential(&pkg.source))
+                    .get_releases(project, pkg)
+                    .await?
+            }
+            PackageSource::Http { versions, .. } => {
+                HttpClient::new(config.credentials.clone())
+                    .get_releases(versions, pkg)
+                    .await?
+            }
+        };
+        if !config.offline {
+            cache_releases(config, &pkg.name, &releases);
+        }
+
+        Ok(releases)
+    }
+
+    /// Get the latest releases of the GitHub packages in batches by GraphQL API if a token is available,
+    /// preceded by the newest releases if they are different (e.g. newer pre-releases).
+    /// The packages not found in the result are expected to be looked up by `get_latest` one by one.
+    /// The releases only contain the versions, so they are not cached for offline mode
+    pub async fn get_latest_releases(&self, pkgs: &[Package]) -> HashMap<String, Vec<Release>> {
+        let config = self.container.get::<Config>().unwrap();
+        let mut releases = hashmap! {};
+
+        if config.offline {
+            return releases;
+        }
+
+        // group by the token, because the credential can be specified per owner
+        let mut token_pkgs: HashMap<String, Vec<Package>> = hashmap! {};
+        for pkg in pkgs {
+            if !matches!(pkg.source, PackageSource::Github { .. })
+                || pkg
+                    .target()
+                    .map_or(true, |it| it.tag_version_regex_template.is_some())
+            {
+                continue;
+            }
+
+            if let Some(credential) = config.to_source_credential(&pkg.source) {
+                token_pkgs
+                    .entry(credential.token)
+                    .or_default()
+                    .push(pkg.clone());
             }
         }
+
+        for pkgs in token_pkgs.values() {
+            let result = match GithubClient::from_source(config, &pkgs[0].source) {
+                Ok(client) => client.get_latest_releases(pkgs).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(latest_releases) => releases.extend(latest_releases),
+                Err(e) => debug!("Failed to get the latest releases by GraphQL API: {}", e),
+            }
+        }
+
+        releases
+    }
+
+    /// Get the release of the package version, or the latest release if the version is not specified
+    pub async fn get_release(&self, obj: &Package) -> anyhow::Result<Release> {
+        let config = self.container.get::<Config>().unwrap();
+
+        // the latest release of the selected channel, instead of falling back to the first release
+        if obj.version.is_none() && obj.channel.is_some() {
+            return self.get_latest(obj).await;
+        }
+
+        if config.offline {
+            return find_offline_release(config, obj, obj.version.as_deref());
+        }
+
+        let mut release = match obj.version {
+            Some(ref v) => {
+                debug!("Getting {} of package release {}", &v, &obj);
+                self.get_version_release(obj, v).await?
+            }
+            None => {
+                debug!("Getting the latest release of package {}", &obj);
+
+                match self.get_latest(obj).await {
+                    Ok(r) => r,
+                    Err(e) => {
+                        debug!(
+                            "Getting the latest pre-release of package {}, because of {}",
+                            &obj, e
+                        );
+                        self.get_releases(obj)
+                            .await?
+                            .into_iter()
+                            .next()
+                            .ok_or_else(|| anyhow!("No release found of package {}", obj.name))?
+                    }
+                }
+            }
+        };
+        release.package.channel = obj.channel.clone();
+
+        Ok(release)
+    }
+
+    // get the release of the package version from its source
+    async fn get_version_release(&self, pkg: &Package, version: &str) -> anyhow::Result<Release> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let release = match &pkg.source {
+            PackageSource::Github { owner, repo }
+            | PackageSource::GithubEnterprise { owner, repo, .. } => {
+                GithubClient::from_source(config, &pkg.source)?
+                    .get_release(owner, repo, version, pkg)
+                    .await?
+            }
+            PackageSource::Gitea { host, owner, repo } => {
+                GiteaClient::new(host, config.to_source_credential(&pkg.source))
+                    .get_release(owner, repo, version, pkg)
+                    .await?
+            }
+            PackageSource::Gitlab { host, project } => {
+                GitlabClient::new(host, config.to_source_credential(&pkg.source))
+                    .get_release(project, version, pkg)
+                    .await?
+            }
+            PackageSource::Http { .. } => {
+                HttpClient::new(config.credentials.clone())
+                    .get_release(version, pkg)
+                    .await?
+            }
+        };
+        cache_releases(config, &pkg.name, slice::from_ref(&release));
+
+        Ok(release)
     }
 
     pub async fn update(
@@ -105,40 +337,13 @@ impl ReleaseService {
         obj: &Package,
         prefer_stdlib: &PlatformStdLib,
         release_check: bool,
+        set_current: bool,
     ) -> anyhow::Result<Release> {
         debug!("Updating release from package: {:#?}", &obj);
 
-        let config = self.container.get::<Config>().unwrap();
-        let client = GithubClient::new(config.to_github_credentials(), config.to_github_key_path());
-
         // Get the release from GitHub
         let mut release = if release_check {
-            match obj.source {
-                PackageSource::Github {
-                    ref owner,
-                    ref repo,
-                } => match obj.version {
-                    Some(ref v) => {
-                        debug!("Getting {} of package release {}", &v, &obj);
-                        client.get_release(owner, repo, v, obj).await?
-                    }
-                    None => {
-                        debug!("Getting the latest release of package {}", &obj);
-
-                        if let Ok(r) = client.get_latest_release(owner, repo, obj).await {
-                            r
-                        } else {
-                            debug!("Getting the latest pre-release of package {}", &obj);
-                            client
-                                .get_releases(owner, repo, obj)
-                                .await?
-                                .first()
-                                .expect("Failed to find the first release")
-                                .to_owned()
-                        }
-                    }
-                },
-            }
+            self.get_release(obj).await?
         } else {
             Release {
                 package: obj.clone(),
@@ -147,166 +352,1329 @@ impl ReleaseService {
                 executables: None,
                 name: "".to_string(),
                 kind: None,
+                assets: None,
+                env: None,
             }
         };
 
-        let release_detail = release.package.detail.clone().or_else(|| {
-            Some(PackageDetailType::Github {
+        let release_detail = release.package.detail.clone().or_else(|| match obj.source {
+            PackageSource::Github { .. }
+            | PackageSource::GithubEnterprise { .. }
+            | PackageSource::Gitea { .. } => Some(PackageDetailType::Github {
                 package: GithubPackage {
                     tag_name: obj.version.clone().unwrap_or_default(),
                     ..Default::default()
                 },
-            })
+            }),
+            PackageSource::Gitlab { .. } => Some(PackageDetailType::Gitlab {
+                package: GitlabPackage {
+                    tag_name: obj.version.clone().unwrap_or_default(),
+                    ..Default::default()
+                },
+            }),
+            PackageSource::Http { .. } => Some(PackageDetailType::Http {
+                package: HttpPackage {
+                    version: obj.version.clone().unwrap_or_default(),
+                },
+            }),
         });
         if release_detail.is_none() {
             return Err(anyhow!("No matched release detail found: {}", release));
         }
 
-        match release_detail.unwrap() {
+        let config = self.container.get::<Config>().unwrap();
+        let staging_dir = Self::clean_staging_dir(config, obj, &release.version)?;
+
+        let assets = match release_detail.unwrap() {
             PackageDetailType::Github { package: p } => {
                 debug!(
                     "Downloading package artifacts from github {:?}",
                     obj.source.url()
                 );
                 self.download_install_github_package(obj, &p, prefer_stdlib)
-                    .await?;
+                    .await
+            }
+            PackageDetailType::Gitlab { package: p } => {
+                debug!(
+                    "Downloading package artifacts from gitlab {:?}",
+                    obj.source.url()
+                );
+                self.download_install_gitlab_package(obj, &p, prefer_stdlib)
+                    .await
+            }
+            PackageDetailType::Http { package: p } => {
+                debug!(
+                    "Downloading package artifacts from http {:?}",
+                    obj.source.url()
+                );
+                self.download_install_http_package(obj, &p).await
+            }
+        }
+        .inspect_err(|_| {
+            let _ = remove_dir_all(&staging_dir);
+        })?;
+        release.assets = Some(assets);
 
-                debug!("Setting {} as the current package", release);
-                let executables = self.set_current(&mut release).await?;
-                info!(
-                    "Installed executables of {}:\n{:#?}",
-                    obj.name, &executables
+        let executables = self
+            .install_staged_release(&mut release, &staging_dir, set_current)
+            .await?;
+        info!(
+            "Installed executables of {}:\n{:#?}",
+            obj.name, &executables
+        );
+        release.executables = Some(executables);
+
+        Ok(release)
+    }
+
+    // remove the leftover of the previous failed installation, and create the staging directory
+    fn clean_staging_dir(config: &Config, pkg: &Package, version: &str) -> anyhow::Result<PathBuf> {
+        let staging_dir = config.staging_pkg_dir(pkg, version)?;
+        remove_dir_all(&staging_dir)?;
+
+        config.staging_pkg_dir(pkg, version)
+    }
+
+    /// Move the staged release to the installed package directory and set it as current if `set_current`.
+    /// If any step fails, the installed package directory, the current links and the current index
+    /// are restored to the previous state
+    async fn install_staged_release(
+        &self,
+        release: &mut Release,
+        staging_dir: &Path,
+        set_current: bool,
+    ) -> anyhow::Result<Vec<String>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let previous_release = self.current(&release.package).ok();
+        let pkg_dir = config.installed_pkg_dir(&release.package, &release.version)?;
+        // not by with_extension, which replaces the patch version of the directory name (e.g. v1.2.3)
+        let backup_dir = staging_dir.with_file_name(format!(
+            "{}.backup",
+            staging_dir.file_name().unwrap().to_string_lossy()
+        ));
+
+        debug!(
+            "Moving the staged release {:?} to {:?}",
+            staging_dir, pkg_dir
+        );
+        let _ = remove_dir_all(&backup_dir);
+        rename(&pkg_dir, &backup_dir)?;
+        if let Err(err) = rename(staging_dir, &pkg_dir) {
+            rename(&backup_dir, &pkg_dir)?;
+            return Err(err.into());
+        }
+
+        // link the executables at install time, so `huber env` evaluated by the shell hooks doesn't write anything
+        let result = if let Err(err) = self.link_executables(&release.package, &release.version) {
+            Err(err)
+        } else if set_current {
+            debug!("Setting {} as the current package", release);
+            self.set_current(release).await
+        } else {
+            self.save_release(release)
+        };
+
+        match result {
+            Ok(executables) => {
+                let _ = remove_dir_all(&backup_dir);
+                Ok(executables)
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to install {}, restoring the previous installation: {}",
+                    release, err
                 );
-                release.executables = Some(executables);
 
-                Ok(release)
+                // unlink before removing the failed release, which the current links may point to
+                if set_current {
+                    self.reset_current(&release.package)?;
+                }
+
+                remove_dir_all(&pkg_dir)?;
+                rename(&backup_dir, &pkg_dir)?;
+                if read_dir(&pkg_dir)?.next().is_none() {
+                    remove_dir_all(&pkg_dir)?;
+                }
+
+                if let Some(previous_release) = previous_release.filter(|_| set_current) {
+                    self.restore_current(&previous_release).await?;
+                }
+
+                Err(err)
             }
         }
     }
 
-    fn get_assets(package: &Package, version: &str) -> anyhow::Result<Vec<String>> {
-        let asset_names: Vec<String> = package
-            .target()?
-            .artifact_templates
-            .iter()
-            .map(|it| {
-                it.replace("{version}", version.trim_start_matches("v"))
-                    .replace("{os}", env::consts::OS)
-                    .replace("{arch}", env::consts::ARCH)
-            })
-            .filter(|it| {
-                let file_name = if let Ok(url) = Url::parse(it) {
-                    url.path_segments().unwrap().last().unwrap().to_string()
-                } else {
-                    it.clone()
-                };
+    // save the manifest of the installed release without setting it as current, e.g. to run it ephemerally
+    fn save_release(&self, release: &mut Release) -> anyhow::Result<Vec<String>> {
+        let config = self.container.get::<Config>().unwrap();
 
-                if file_name.contains('.') && !file_name.ends_with(".exe") {
-                    SUPPORTED_ARCHIVE_TYPES
-                        .iter()
-                        .any(|ext| file_name.ends_with(ext))
-                } else {
-                    true
-                }
-            })
+        release.current = false;
+        release.name = release.package.name.clone();
+
+        let executables: Vec<_> = self
+            .get_executables(&release.package, &release.version)?
+            .into_values()
+            .map(|it| it.to_string_lossy().to_string())
             .collect();
+        if executables.is_empty() {
+            return Err(anyhow!("No executables found when installing {}", &release));
+        }
 
-        Ok(asset_names)
+        let pkg_dir = config.installed_pkg_dir(&release.package, &release.version)?;
+        release.env = release
+            .package
+            .get_release_env(&release.version, &pkg_dir)?;
+
+        let release_f = config.installed_pkg_manifest_file(&release.package, &release.version)?;
+        serde_yaml::to_writer(File::create(release_f)?, &release)?;
+
+        Ok(executables)
     }
 
-    async fn download_assets(
+    /// Check if any versions of the package are installed, whether or not one of them is current
+    pub fn has_installed_versions(&self, pkg: &Package) -> anyhow::Result<bool> {
+        let config = self.container.get::<Config>().unwrap();
+
+        for entry in read_dir(config.installed_pkg_base_dir(pkg)?)? {
+            let path = entry?.path();
+            let filename = path.file_name().unwrap().to_string_lossy().to_string();
+            if filename == "current" || !path.is_dir() {
+                continue;
+            }
+
+            if config.installed_pkg_manifest_file(pkg, &filename)?.exists() {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Get the previously current versions of the package, the most recent last
+    pub fn get_current_history(&self, pkg: &Package) -> anyhow::Result<Vec<ReleaseHistory>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let history_f = config.current_history_file(pkg)?;
+        if !history_f.exists() {
+            return Ok(vec![]);
+        }
+
+        Ok(serde_yaml::from_reader(File::open(history_f)?)?)
+    }
+
+    fn save_current_history(
         &self,
-        package: &Package,
-        config: &Config,
-        version: &str,
-        download_urls: &mut Vec<String>,
+        pkg: &Package,
+        history: &[ReleaseHistory],
     ) -> anyhow::Result<()> {
-        let mut tasks = vec![];
+        let config = self.container.get::<Config>().unwrap();
 
-        'download: for download_url in download_urls {
-            let pkg_dir = config.installed_pkg_dir(package, version)?;
-            let filename = download_url.split("/").last().unwrap().to_string();
-            let download_file_path = config.temp_dir()?.join(&filename);
+        let history_f = config.current_history_file(pkg)?;
+        debug!(
+            "Saving the current version history of {} to {:?}",
+            pkg.name, history_f
+        );
 
-            let mut ext = "";
-            let trimmed_filename = trim_os_arch_version(&filename);
+        let history = &history[history.len().saturating_sub(MAX_CURRENT_HISTORY)..];
+        Ok(serde_yaml::to_writer(File::create(history_f)?, history)?)
+    }
 
-            for (i, &archive_type) in SUPPORTED_ARCHIVE_TYPES.iter().enumerate() {
-                if trimmed_filename.ends_with(archive_type) {
-                    ext = archive_type;
-                    break;
-                }
-                if !has_suffix(&trimmed_filename) {
-                    break;
-                }
-                if i == SUPPORTED_ARCHIVE_TYPES.len() - 1 {
-                    debug!("Ignored to download {} due to unsupported archive type. Supported types: {:?}",&download_url, SUPPORTED_ARCHIVE_TYPES);
-                    continue 'download;
+    /// Get the executables of the installed release by the names linked in the bin directory
+    pub fn get_executables(
+        &self,
+        pkg: &Package,
+        version: &str,
+    ) -> anyhow::Result<HashMap<String, PathBuf>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let pkg_dir = config.installed_pkg_dir(pkg, version)?;
+        let pkg_bin_dir = pkg_dir.join("bin");
+
+        Ok(scan_executables(pkg, &[pkg_dir, pkg_bin_dir])?
+            .into_iter()
+            .collect())
+    }
+
+    /// Get the environment of the installed release, which is resolved from the package
+    /// if not recorded in the release manifest, e.g. installed by older versions
+    pub fn get_release_env(&self, release: &Release) -> anyhow::Result<Option<ReleaseEnv>> {
+        if release.env.is_some() {
+            return Ok(release.env.clone());
+        }
+
+        let config = self.container.get::<Config>().unwrap();
+        let install_dir = config.installed_pkg_dir(&release.package, &release.version)?;
+
+        release
+            .package
+            .get_release_env(&release.version, &install_dir)
+    }
+
+    /// Get the directories of the executables of the installed release, which can be added to PATH
+    /// to run the release without switching the current release. They are the directories of the executables
+    /// if the release was installed without the link directory, e.g. by older versions
+    pub fn get_executable_dirs(
+        &self,
+        pkg: &Package,
+        version: &str,
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let link_dir = config.installed_pkg_link_dir(pkg, version)?;
+        if link_dir.is_dir() {
+            return Ok(vec![link_dir]);
+        }
+
+        let mut dirs = vec![];
+        for exec_path in self.get_executables(pkg, version)?.into_values() {
+            if let Some(exec_dir) = exec_path.parent().map(Path::to_path_buf) {
+                if !dirs.contains(&exec_dir) {
+                    dirs.push(exec_dir);
                 }
             }
+        }
 
-            let task = async move {
-                info!("Downloading {}", &download_url);
-                debug!("Downloading {} to {:?}", &download_url, &download_file_path);
+        Ok(dirs)
+    }
 
-                let _ = remove_file(&download_file_path);
-                let _ = remove_dir_all(&download_file_path);
+    // link the executables of the installed release by their names in its link directory
+    fn link_executables(&self, pkg: &Package, version: &str) -> anyhow::Result<()> {
+        let config = self.container.get::<Config>().unwrap();
 
-                let response = reqwest::get(download_url.to_string()).await?;
-                match response.error_for_status() {
-                    Err(e) => return Err(anyhow!("{:?}", e)),
+        let link_dir = dir(config.installed_pkg_link_dir(pkg, version)?)?;
+        for (exec_name, exec_path) in self.get_executables(pkg, version)? {
+            let exec_link = link_dir.join(exec_name);
+            if exec_link.symlink_metadata().is_err() {
+                symlink_file(&exec_path, &exec_link)?;
+            }
+        }
 
-                    Ok(response) => {
-                        let mut dest_f = File::create(&download_file_path)?;
-                        let bytes = response.bytes().await?;
-                        dest_f.write_all(&bytes)?;
-                    }
-                }
+        Ok(())
+    }
 
-                // downloaded asset seems an executable instead of an archive, move it to the package directory
-                if ext.is_empty() {
-                    let dest_f = pkg_dir.join(&filename);
+    /// Link the executables of the installed releases in the bin directory with version suffixes (e.g. kubectl-1.29),
+    /// if enabled for the package by `versioned_executables` in the config. The suffix of the higher version
+    /// is linked if multiple versions have the same suffix, and the existing executables are never replaced
+    pub async fn link_versioned_executables(&self, pkg: &Package) -> anyhow::Result<Vec<String>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        self.unlink_versioned_executables(pkg)?;
+        if !config.versioned_executables.contains(&pkg.name) {
+            return Ok(vec![]);
+        }
+
+        let mut releases = self.find(pkg).await?;
+        releases.sort_by_version();
+
+        let bin_dir = config.bin_dir()?;
+        let mut results = vec![];
+
+        for release in releases {
+            let suffix = get_version_suffix(&pkg.parse_version_from_tag_name(&release.version)?);
+
+            for (exec_name, exec_path) in self.get_executables(pkg, &release.version)? {
+                let exec_name = match exec_name.strip_suffix(".exe") {
+                    Some(name) => format!("{}-{}.exe", name, suffix),
+                    None => format!("{}-{}", exec_name, suffix),
+                };
 
+                let exec_link = bin_dir.join(&exec_name);
+                if exec_link.symlink_metadata().is_ok() {
                     debug!(
-                        "Moving {:?} to {:?}, because it's not an archive, regarded as an executable",
-                        &download_file_path, &dest_f
+                        "Ignored linking {:?} of {}, because {:?} exists",
+                        exec_path, release, exec_link
                     );
-                    set_executable_permission(&download_file_path)?;
+                    continue;
+                }
 
-                    let option = fs_extra::file::CopyOptions {
-                        overwrite: false,
-                        skip_exist: true,
-                        buffer_size: 0,
-                    };
-                    fs_extra::file::move_file(&download_file_path, &dest_f, &option)?;
+                symlink_file(&exec_path, &exec_link)?;
+                results.push(exec_link.to_string_lossy().to_string());
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Remove the versioned executables of the package linked in the bin directory
+    pub fn unlink_versioned_executables(&self, pkg: &Package) -> anyhow::Result<()> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let pkg_base_dir = config.installed_pkg_base_dir(pkg)?;
+        let current_pkg_dir = config.current_pkg_dir(pkg)?;
+
+        // the links to the installed releases other than the current links
+        for entry in read_dir(config.bin_dir()?)? {
+            let path = entry?.path();
+            let Ok(target) = path.read_link() else {
+                continue;
+            };
+
+            if target.starts_with(&pkg_base_dir) && !target.starts_with(&current_pkg_dir) {
+                debug!("Removing link {:?}", path);
+                remove_symlink_file(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the shims linked in the bin directory
+    pub fn get_shim_indexes(&self) -> anyhow::Result<Vec<ShimIndex>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let index_f = config.shim_index_file()?;
+        if !index_f.exists() {
+            return Ok(vec![]);
+        }
+
+        Ok(serde_yaml::from_reader(File::open(index_f)?)?)
+    }
+
+    // replace the shims of the package, or remove them if empty
+    fn update_shim_indexes(&self, pkg: &Package, shims: Vec<ShimIndex>) -> anyhow::Result<()> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let mut indexes = self.get_shim_indexes()?;
+        indexes.retain(|it| {
+            it.package.name != pkg.name && !shims.iter().any(|s| s.executable == it.executable)
+        });
+        indexes.extend(shims);
+
+        let index_f = config.shim_index_file()?;
+        debug!("Updating the shim index manifest: {:?}", index_f);
+
+        let _ = remove_file(&index_f);
+        if !indexes.is_empty() {
+            serde_yaml::to_writer(File::create(index_f)?, &indexes)?;
+        }
+
+        Ok(())
+    }
+
+    /// Roll back the package to the previously current version in the history.
+    /// The versions not installed anymore are skipped
+    pub async fn rollback(&self, pkg: &Package) -> anyhow::Result<Release> {
+        debug!("Rolling back the current release: {}", pkg);
+
+        let current_release = self.current(pkg)?;
+        let installed_releases = self.find(pkg).await?;
+        let mut history = self.get_current_history(pkg)?;
+
+        while let Some(entry) = history.pop() {
+            if entry.version == current_release.version {
+                continue;
+            }
+
+            match installed_releases
+                .iter()
+                .find(|it| it.version == entry.version)
+            {
+                Some(release) => {
+                    let mut release = release.clone();
+                    let executables = self.switch_current(&mut release, false).await?;
+                    release.executables = Some(executables);
+                    self.save_current_history(pkg, &history)?;
+
+                    return Ok(release);
+                }
+                None => debug!(
+                    "Skipped rolling back {} to {}, because it is not installed anymore",
+                    pkg.name, entry.version
+                ),
+            }
+        }
+
+        self.save_current_history(pkg, &history)?;
+        Err(anyhow!(HuberError::NoRollbackRelease(pkg.name.clone())))
+    }
+
+    /// Restore the installed release as current without recording the history, e.g. after a failed update
+    pub async fn restore_current(&self, release: &Release) -> anyhow::Result<Vec<String>> {
+        debug!("Restoring the current release: {}", release);
+
+        let mut release = release.clone();
+        release.executables = None;
+        self.switch_current(&mut release, false).await
+    }
+
+    // set the release as current, and record the previously current version in the history if required
+    async fn switch_current(
+        &self,
+        release: &mut Release,
+        record_history: bool,
+    ) -> anyhow::Result<Vec<String>> {
+        debug!("Setting the current release: {}", &release);
+
+        release.current = true;
+        release.name = release.package.name.clone();
+
+        debug!(
+            "Removing the old current release symbolic links: {}",
+            &release.package
+        );
+        self.reset_current(&release.package)?;
+
+        debug!("Updating the current release symbolic links: {}", &release);
+
+        let config = self.container.get::<Config>().unwrap();
+        let current_pkg_dir = config.current_pkg_dir(&release.package)?;
+        let source = config.installed_pkg_dir(&release.package, &release.version)?;
+
+        symlink_dir(&source, &current_pkg_dir)?;
+
+        let linked_exe_files = self.get_executables_for_current(&release.package, true)?;
+        if linked_exe_files.is_empty() {
+            return Err(anyhow!("No executables found when installing {}", &release));
+        }
+
+        // update old current release manifest
+        let index_f = config.current_index_file()?;
+        let mut indexes: Vec<ReleaseIndex> = vec![];
+        let mut previous_version: Option<String> = None;
+
+        debug!("Updating the current index manifest: {:?}", &index_f);
+
+        if index_f.exists() {
+            let f = File::open(&index_f)?;
+            indexes = serde_yaml::from_reader(&f)?;
+
+            if let Some(found) = indexes.iter().find(|it| it.name == release.package.name) {
+                previous_version = Some(found.version.clone());
+                let old_pkg_manifest_path =
+                    config.installed_pkg_manifest_file(&release.package, &found.version)?;
+                let f = File::open(&old_pkg_manifest_path)?;
+
+                let mut r: Release = serde_yaml::from_reader(f)?;
+                r.current = false;
+
+                let _ = remove_file(&old_pkg_manifest_path);
+                let f = File::create(&old_pkg_manifest_path)?;
+                serde_yaml::to_writer(f, &r)?;
+            }
+
+            indexes.retain(|it| it.name != release.package.name);
+        }
+
+        indexes.push(ReleaseIndex {
+            name: release.package.name.clone(),
+            version: release.version.clone(),
+            owner: release.package.source.owner().to_string(),
+            source: release.package.source.to_string(),
+        });
+
+        // update current release index file
+        let _ = remove_file(&index_f);
+        let index_f = File::create(&index_f)?;
+        serde_yaml::to_writer(index_f, &indexes)?;
+
+        // record the environment declared by the package for the installed directory
+        release.env = release.package.get_release_env(&release.version, &source)?;
+
+        let release_f = config.installed_pkg_manifest_file(&release.package, &release.version)?;
+        let _ = remove_file(&release_f);
+        let release_f = File::create(release_f)?;
+        serde_yaml::to_writer(release_f, &release)?;
+
+        // clean other installed releases as non-current
+        let releases = self.find(&release.package).await?;
+        let inactive_releases: Vec<&Release> = releases
+            .iter()
+            .filter(|it| it.version != release.version)
+            .collect();
+
+        for r in inactive_releases {
+            self.clean_current(r)?;
+        }
+
+        if let Some(version) =
+            previous_version.filter(|it| record_history && *it != release.version)
+        {
+            let mut history = self.get_current_history(&release.package)?;
+            history.push(ReleaseHistory {
+                version,
+                switched_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            });
+            self.save_current_history(&release.package, &history)?;
+        }
+
+        // the versioned executables are auxiliary, so failing to link them doesn't fail switching
+        if let Err(e) = self.link_versioned_executables(&release.package).await {
+            warn!(
+                "Failed to link the versioned executables of {}: {}",
+                release.package.name, e
+            );
+        }
+
+        Ok(linked_exe_files)
+    }
+
+    /// Install the package from the exact assets instead of the ones resolved from the release,
+    /// and verify the downloaded artifacts against the recorded checksums
+    pub async fn update_from_assets(
+        &self,
+        obj: &Package,
+        assets: &[ReleaseAsset],
+    ) -> anyhow::Result<Release> {
+        debug!("Updating release from assets: {:#?}", assets);
+
+        let config = self.container.get::<Config>().unwrap();
+        let tag = obj.version.clone().unwrap_or_default();
+        let version = obj.parse_version_from_tag_name(&tag)?;
+
+        let mut download_urls: Vec<String> = assets.iter().map(|it| it.url.clone()).collect();
+        let checksums: HashMap<String, String> = assets
+            .iter()
+            .map(|it| {
+                let filename = it.url.split("/").last().unwrap_or_default();
+                (filename.to_string(), it.checksum.clone())
+            })
+            .collect();
+
+        let staging_dir = Self::clean_staging_dir(config, obj, &version)?;
+        let assets = self
+            .download_assets(
+                obj,
+                config,
+                &version,
+                &mut download_urls,
+                &Some(checksums),
+                &None,
+            )
+            .await
+            .inspect_err(|_| {
+                let _ = remove_dir_all(&staging_dir);
+            })?;
+
+        let mut release = Release {
+            name: obj.name.clone(),
+            version: tag,
+            current: false,
+            package: obj.clone(),
+            executables: None,
+            kind: None,
+            assets: Some(assets),
+            env: None,
+        };
+
+        debug!("Setting {} as the current package", release);
+        let executables = self
+            .install_staged_release(&mut release, &staging_dir, true)
+            .await?;
+        info!(
+            "Installed executables of {}:\n{:#?}",
+            obj.name, &executables
+        );
+        release.executables = Some(executables);
+
+        Ok(release)
+    }
+
+    fn get_assets(
+        package: &Package,
+        version: &str,
+        os: &str,
+        arch: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let asset_names: Vec<String> = package
+            .target_of(os, arch)?
+            .artifact_templates
+            .iter()
+            .map(|it| {
+                it.replace("{version}", version.trim_start_matches("v"))
+                    .replace("{os}", os)
+                    .replace("{arch}", arch)
+            })
+            .filter(|it| {
+                let file_name = if let Ok(url) = Url::parse(it) {
+                    url.path_segments().unwrap().last().unwrap().to_string()
+                } else {
+                    it.clone()
+                };
+
+                if file_name.contains('.') && !file_name.ends_with(".exe") {
+                    SUPPORTED_ARCHIVE_TYPES
+                        .iter()
+                        .any(|ext| file_name.ends_with(ext))
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        Ok(asset_names)
+    }
+
+    /// Get the URLs of the artifacts, checksum files and signatures of the release on the platform
+    pub fn get_release_artifact_urls(
+        &self,
+        release: &Release,
+        os: &str,
+        arch: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let package = &release.package;
+
+        let (tag_name, release_assets): (&String, Vec<(String, String)>) = match &package.detail {
+            Some(PackageDetailType::Github { package: p }) => (
+                &p.tag_name,
+                p.assets
+                    .iter()
+                    .map(|it| {
+                        decode(&it.browser_download_url)
+                            .map(|url| (it.name.clone(), url.to_string()))
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+            Some(PackageDetailType::Gitlab { package: p }) => (
+                &p.tag_name,
+                p.assets
+                    .iter()
+                    .map(|it| {
+                        let url = it.direct_asset_url.clone().unwrap_or(it.url.clone());
+                        (it.name.clone(), url)
+                    })
+                    .collect(),
+            ),
+            Some(PackageDetailType::Http { package: p }) => (&p.version, vec![]),
+            None => return Err(anyhow!("No matched release detail found: {}", release)),
+        };
+        let version = package.parse_version_from_tag_name(tag_name)?;
+
+        let asset_download_urls: Vec<String> = match &package.detail {
+            Some(PackageDetailType::Http { .. }) => Self::get_assets(package, &version, os, arch)?
+                .iter()
+                .filter_map(|it| package.source.resolve_url(it, &version))
+                .collect(),
+            _ => Self::get_asset_download_urls(package, &version, &release_assets, os, arch)?,
+        };
+        let release_asset_urls: Vec<String> =
+            release_assets.into_iter().map(|(_, url)| url).collect();
+
+        let mut urls = Self::get_checksum_urls(package, &version, &release_asset_urls, os, arch)?
+            .unwrap_or_default();
+        if let Some(signature_urls) = Self::get_signature_urls(
+            package,
+            &version,
+            &release_asset_urls,
+            &asset_download_urls,
+            os,
+            arch,
+        )? {
+            urls.extend(signature_urls.into_iter().map(|(_, url)| url));
+        }
+        urls.extend(asset_download_urls);
+
+        Ok(urls)
+    }
+
+    /// Download the artifacts of the release on the platforms, which are pairs of OS and ARCH, to the mirror
+    pub async fn mirror_release(
+        &self,
+        release: &Release,
+        platforms: &[(String, String)],
+        mirror: &Mirror,
+    ) -> anyhow::Result<Vec<String>> {
+        let config = self.container.get::<Config>().unwrap();
+
+        let mut urls: Vec<String> = vec![];
+        for (os, arch) in platforms {
+            match self.get_release_artifact_urls(release, os, arch) {
+                Ok(platform_urls) => {
+                    for url in platform_urls {
+                        if !urls.contains(&url) {
+                            urls.push(url);
+                        }
+                    }
+                }
+                Err(e) => warn!("Skipped mirroring {} for {}/{}: {}", release, os, arch, e),
+            }
+        }
+
+        let artifact_cache = if config.artifact_cache.enabled {
+            Some(ArtifactCache::from_config(config)?)
+        } else {
+            None
+        };
+        let progress =
+            matches!(config.output_format, OutputFormat::Console).then(MultiProgress::new);
+
+        for url in &urls {
+            let path = mirror.artifact_path(url)?;
+            if path.exists() {
+                debug!("Skipped mirroring {}, because it's already mirrored", url);
+                continue;
+            }
+
+            let cached_file = artifact_cache
+                .as_ref()
+                .and_then(|it| it.get(url, None).ok().flatten());
+            if let Some(cached_file) = cached_file {
+                info!("Using the cached artifact of {}", url);
+                copy(cached_file, &path)?;
+                continue;
+            }
+
+            info!("Downloading {}", url);
+            download_file(
+                url,
+                &path,
+                &config.download_retry,
+                progress.as_ref(),
+                || self.download_request(&release.package, url),
+            )
+            .await?;
+        }
+
+        Ok(urls)
+    }
+
+    /// Download the content of the URL with the credential of the host if any,
+    /// or read it from the mirror directory in offline mode
+    async fn download_text(&self, package: &Package, url: &str) -> anyhow::Result<String> {
+        let config = self.container.get::<Config>().unwrap();
+
+        if config.offline {
+            return Ok(read_to_string(find_offline_artifact(config, url)?)?);
+        }
+
+        Ok(self
+            .download_request(package, url)
+            .await?
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?)
+    }
+
+    async fn download_request(
+        &self,
+        package: &Package,
+        url: &str,
+    ) -> anyhow::Result<reqwest::RequestBuilder> {
+        let config = self.container.get::<Config>().unwrap();
+
+        // private GitHub release assets are only downloadable via the asset API, which redirects to the storage
+        if let Some(credential) = config.to_source_credential(&package.source) {
+            if let Some(asset_url) = self.get_github_asset_api_url(package, url).await? {
+                debug!("Downloading {} via the GitHub asset API {}", url, asset_url);
+
+                let request = reqwest::Client::new()
+                    .get(&asset_url)
+                    .header(ACCEPT, "application/octet-stream")
+                    .header(USER_AGENT, "huber");
+                return Ok(credential.authorize(request));
+            }
+        }
+
+        let mut request = reqwest::Client::new().get(url);
+        if let Some(credential) = config.to_download_credential(&package.source, url) {
+            debug!("Downloading {} with the credential of the host", url);
+            request = credential.authorize(request);
+        }
+
+        Ok(request)
+    }
+
+    /// Get the API URL of the GitHub release asset from the release detail of the package,
+    /// or the release of the tag in the download URL
+    async fn get_github_asset_api_url(
+        &self,
+        package: &Package,
+        url: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let (owner, repo) = match &package.source {
+            PackageSource::Github { owner, repo }
+            | PackageSource::GithubEnterprise { owner, repo, .. } => (owner, repo),
+            _ => return Ok(None),
+        };
+
+        let Some((tag, name)) = url
+            .strip_prefix(&format!("{}/releases/download/", package.source.url()))
+            .and_then(|it| it.split_once('/'))
+        else {
+            return Ok(None);
+        };
+        let tag = decode(tag)?.to_string();
+
+        let assets = match &package.detail {
+            Some(PackageDetailType::Github { package: p })
+                if p.tag_name == tag && !p.assets.is_empty() =>
+            {
+                p.assets.clone()
+            }
+            _ => {
+                let config = self.container.get::<Config>().unwrap();
+                let release = GithubClient::from_source(config, &package.source)?
+                    .get_release(owner, repo, &tag, package)
+                    .await?;
+
+                match release.package.detail {
+                    Some(PackageDetailType::Github { package: p }) => p.assets,
+                    _ => vec![],
+                }
+            }
+        };
+
+        Ok(assets
+            .into_iter()
+            .find(|it| it.name == name && !it.url.is_empty())
+            .map(|it| it.url))
+    }
+
+    async fn download_assets(
+        &self,
+        package: &Package,
+        config: &Config,
+        version: &str,
+        download_urls: &mut Vec<String>,
+        checksums: &Option<HashMap<String, String>>,
+        signatures: &Option<HashMap<String, String>>,
+    ) -> anyhow::Result<Vec<ReleaseAsset>> {
+        let package_signature = package.target()?.signature;
+
+        // progress bars are only for the console output, not to break json/yaml outputs
+        let progress =
+            matches!(config.output_format, OutputFormat::Console).then(MultiProgress::new);
+        let artifact_cache = if config.artifact_cache.enabled {
+            Some(ArtifactCache::from_config(config)?)
+        } else {
+            None
+        };
+        let mut tasks = vec![];
+
+        'download: for download_url in download_urls {
+            let pkg_dir = config.staging_pkg_dir(package, version)?;
+            let filename = download_url.split("/").last().unwrap().to_string();
+            let download_file_path = config.temp_dir()?.join(&filename);
+
+            let mut ext = "";
+            let trimmed_filename = trim_os_arch_version(&filename);
+
+            for (i, &archive_type) in SUPPORTED_ARCHIVE_TYPES.iter().enumerate() {
+                if trimmed_filename.ends_with(archive_type) {
+                    ext = archive_type;
+                    break;
+                }
+                if !has_suffix(&trimmed_filename) {
+                    break;
+                }
+                if i == SUPPORTED_ARCHIVE_TYPES.len() - 1 {
+                    debug!("Ignored to download {} due to unsupported archive type. Supported types: {:?}",&download_url, SUPPORTED_ARCHIVE_TYPES);
+                    continue 'download;
+                }
+            }
+
+            let package_signature = &package_signature;
+            let progress = &progress;
+            let artifact_cache = &artifact_cache;
+            let task = async move {
+                let _ = remove_file(&download_file_path);
+                let _ = remove_dir_all(&download_file_path);
+
+                let expected_checksum = checksums
+                    .as_ref()
+                    .and_then(|it| it.get(&filename))
+                    .map(|it| it.as_str());
+                let cached_file = match artifact_cache {
+                    Some(cache) => cache
+                        .get(download_url, expected_checksum)
+                        .unwrap_or_else(|e| {
+                            warn!("Failed to look up the artifact cache: {}", e);
+                            None
+                        }),
+                    None => None,
+                };
+
+                if let Some(cached_file) = &cached_file {
+                    info!("Using the cached artifact of {}", &download_url);
+                    debug!("Copying {:?} to {:?}", cached_file, &download_file_path);
+                    copy(cached_file, &download_file_path)?;
+                } else if config.offline {
+                    let mirrored_file = find_offline_artifact(config, download_url)?;
+                    info!("Using the mirrored artifact of {}", &download_url);
+                    debug!("Copying {:?} to {:?}", mirrored_file, &download_file_path);
+                    copy(mirrored_file, &download_file_path)?;
+                } else {
+                    info!("Downloading {}", &download_url);
+                    debug!("Downloading {} to {:?}", &download_url, &download_file_path);
+
+                    download_file(
+                        download_url,
+                        &download_file_path,
+                        &config.download_retry,
+                        progress.as_ref(),
+                        || self.download_request(package, download_url),
+                    )
+                    .await
+                    .map_err(|e| anyhow!("{:?}", e))?;
+                }
+
+                if let Some(checksums) = checksums {
+                    match checksums.get(&filename) {
+                        Some(checksum) => {
+                            debug!("Verifying the checksum of {:?}", &download_file_path);
+                            verify_checksum(&download_file_path, &filename, checksum)?;
+                        }
+                        None => return Err(anyhow!(HuberError::ChecksumNotFound(filename))),
+                    }
+                }
+
+                if let (Some(signatures), Some(package_signature)) = (signatures, package_signature)
+                {
+                    match signatures.get(&filename) {
+                        Some(signature) => {
+                            debug!("Verifying the signature of {:?}", &download_file_path);
+                            verify_signature(
+                                package_signature,
+                                &download_file_path,
+                                &filename,
+                                signature,
+                            )?;
+                        }
+                        None => return Err(anyhow!(HuberError::SignatureNotFound(filename))),
+                    }
+                }
+
+                let asset = ReleaseAsset {
+                    url: download_url.to_string(),
+                    checksum: sha256_file(&download_file_path)?,
+                };
+
+                // only verified artifacts are cached
+                if let (Some(cache), None) = (artifact_cache, &cached_file) {
+                    if let Err(e) = cache.put(download_url, &download_file_path, &asset.checksum) {
+                        warn!("Failed to cache the artifact of {}: {}", download_url, e);
+                    }
+                }
+
+                // downloaded asset seems an executable instead of an archive, move it to the package directory
+                if ext.is_empty() {
+                    let dest_f = pkg_dir.join(&filename);
+
+                    debug!(
+                        "Moving {:?} to {:?}, because it's not an archive, regarded as an executable",
+                        &download_file_path, &dest_f
+                    );
+                    set_executable_permission(&download_file_path)?;
+
+                    let option = fs_extra::file::CopyOptions {
+                        overwrite: false,
+                        skip_exist: true,
+                        buffer_size: 0,
+                    };
+                    fs_extra::file::move_file(&download_file_path, &dest_f, &option)?;
+
+                    return Ok(asset);
+                }
+
+                Self::decompress_asset(&pkg_dir, &filename, &download_file_path, ext)?;
+
+                Ok(asset)
+            };
+
+            tasks.push(task);
+        }
+
+        let (assets, errors): (Vec<_>, Vec<_>) = futures::future::join_all(tasks)
+            .await
+            .into_iter()
+            .partition(|r| r.is_ok());
+        let errors: Vec<_> = errors
+            .into_iter()
+            .filter_map(|r| r.err())
+            .inspect(|e| error!("Failed to download asset: {}", e))
+            .collect();
+
+        if let Some(e) = errors.into_iter().next() {
+            // surface integrity errors as they are instead of a general download failure
+            if e.downcast_ref::<HuberError>().is_some() {
+                return Err(e);
+            }
+            return Err(anyhow!("Failed to download assets"));
+        }
+
+        Ok(assets.into_iter().filter_map(|r| r.ok()).collect())
+    }
+
+    /// Get the download URLs of the release assets matched with the artifacts of the package on the platform.
+    /// Release assets are pairs of the asset name and download URL
+    fn get_asset_download_urls(
+        package: &Package,
+        version: &str,
+        release_assets: &[(String, String)],
+        os: &str,
+        arch: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut asset_names = Self::get_assets(package, version, os, arch)?;
+
+        let mut ext_asset_urls: Vec<String> = asset_names // external assets not in the release
+            .iter()
+            .filter(|it| Url::parse(it).is_ok() && it.starts_with("https"))
+            .cloned()
+            .collect();
+        let mut asset_download_urls: Vec<String> = vec![];
+        asset_download_urls.append(&mut ext_asset_urls);
+
+        if !ext_asset_urls.is_empty() {
+            asset_names.retain(|it| !ext_asset_urls.contains(it));
+        }
+
+        for (asset_name, asset_url) in release_assets.iter() {
+            if asset_names.is_empty() {
+                debug!(
+                    "Checking {} if it's in the expected os/arch type: {}, since there are no expected artifact names defined",
+                    asset_name, asset_url
+                );
+                if !is_os_arch_match(os, arch, &asset_url.to_lowercase()) {
+                    continue;
+                }
+            } else {
+                debug!(
+                    "Checking {} if it's in the expected artifact names: {:?}",
+                    asset_name, asset_names
+                );
+                if !asset_names.contains(asset_name)
+                    && !asset_names.iter().any(|it| asset_url.ends_with(it))
+                {
+                    debug!(
+                    "Ignored {}, not mentioned or not right arch type defined in the package artifact config",
+                    asset_name
+                );
+                    continue;
+                }
+            }
+
+            debug!("Found asset: {}", asset_url);
+            asset_download_urls.push(asset_url.to_string());
+        }
+
+        if !release_assets.is_empty() && asset_download_urls.is_empty() {
+            return Err(anyhow!(
+                "No available artifacts for {} to download. Expected artifact names: {:?}",
+                package.name,
+                asset_names
+            ));
+        }
+
+        Ok(asset_download_urls)
+    }
+
+    /// Download and install the matched release assets, which are pairs of the asset name and download URL
+    async fn download_install_release_assets(
+        &self,
+        package: &Package,
+        tag_name: &str,
+        release_assets: &[(String, String)],
+        prefer_stdlib: &PlatformStdLib,
+    ) -> anyhow::Result<Vec<ReleaseAsset>> {
+        let config = self.container.get::<Config>().unwrap();
+        let version = package.parse_version_from_tag_name(&tag_name.to_string())?;
+
+        let mut asset_download_urls = Self::get_asset_download_urls(
+            package,
+            &version,
+            release_assets,
+            env::consts::OS,
+            env::consts::ARCH,
+        )?;
+
+        if prefer_stdlib != &PlatformStdLib::None {
+            info!(
+                "Prefer downloading assets for {} belonging to the specified stdlib: {}",
+                package.name,
+                prefer_stdlib
+            );
+
+            let stdlib_regex = Regex::new(&format!(
+                r"\b{}\b?",
+                prefer_stdlib.to_string().to_lowercase()
+            ))?;
+            let results: Vec<_> = asset_download_urls
+                .clone()
+                .into_iter()
+                .filter(|it| {
+                    let filename = Url::parse(it)
+                        .unwrap()
+                        .path_segments()
+                        .and_then(|segments| segments.last())
+                        .unwrap_or_default()
+                        .to_string();
+                    stdlib_regex.is_match(&filename)
+                })
+                .collect();
+            if !results.is_empty() {
+                asset_download_urls = results;
+            }
+        }
+
+        let release_asset_urls: Vec<String> =
+            release_assets.iter().map(|(_, url)| url.clone()).collect();
+
+        let checksums = self
+            .download_checksums(package, &version, &release_asset_urls)
+            .await?;
+
+        let signatures = self
+            .download_signatures(package, &version, &release_asset_urls, &asset_download_urls)
+            .await?;
+
+        self.download_assets(
+            package,
+            config,
+            &version,
+            &mut asset_download_urls,
+            &checksums,
+            &signatures,
+        )
+        .await
+    }
+
+    async fn download_checksums(
+        &self,
+        package: &Package,
+        version: &str,
+        release_asset_urls: &[String],
+    ) -> anyhow::Result<Option<HashMap<String, String>>> {
+        let checksum_urls = match Self::get_checksum_urls(
+            package,
+            version,
+            release_asset_urls,
+            env::consts::OS,
+            env::consts::ARCH,
+        )? {
+            Some(checksum_urls) => checksum_urls,
+            None => return Ok(None),
+        };
+
+        let mut checksums: HashMap<String, String> = hashmap! {};
+        for url in checksum_urls {
+            info!("Downloading checksums {}", &url);
+
+            let content = self.download_text(package, &url).await?;
+            let filename = url.split("/").last().unwrap_or_default();
+            checksums.extend(parse_checksums(&content, filename));
+        }
+
+        Ok(Some(checksums))
+    }
+
+    /// Get the URLs of the checksum files of the package on the platform, None if no checksum template defined
+    fn get_checksum_urls(
+        package: &Package,
+        version: &str,
+        release_asset_urls: &[String],
+        os: &str,
+        arch: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        let template = match package.target_of(os, arch)?.checksum_template {
+            Some(template) => template
+                .replace("{version}", version.trim_start_matches("v"))
+                .replace("{os}", os)
+                .replace("{arch}", arch),
+            None => return Ok(None),
+        };
+
+        let checksum_urls: Vec<String> =
+            if Url::parse(&template).is_ok() && template.starts_with("https") {
+                vec![template.clone()]
+            } else if let Some(url) = package.source.resolve_url(&template, version) {
+                vec![url]
+            } else {
+                let regex = Regex::new(&format!(
+                    "^{}$",
+                    regex::escape(&template).replace(r"\*", ".*")
+                ))?;
+
+                release_asset_urls
+                    .iter()
+                    .filter(|it| regex.is_match(it.split("/").last().unwrap_or_default()))
+                    .cloned()
+                    .collect()
+            };
 
-                    return Ok(());
-                }
+        if checksum_urls.is_empty() {
+            return Err(anyhow!(HuberError::ChecksumNotFound(template)));
+        }
 
-                Self::decompress_asset(&pkg_dir, &filename, &download_file_path, ext)?;
+        Ok(Some(checksum_urls))
+    }
 
-                Ok(())
-            };
+    async fn download_signatures(
+        &self,
+        package: &Package,
+        version: &str,
+        release_asset_urls: &[String],
+        download_urls: &[String],
+    ) -> anyhow::Result<Option<HashMap<String, String>>> {
+        let signature_urls = match Self::get_signature_urls(
+            package,
+            version,
+            release_asset_urls,
+            download_urls,
+            env::consts::OS,
+            env::consts::ARCH,
+        )? {
+            Some(signature_urls) => signature_urls,
+            None => return Ok(None),
+        };
 
-            tasks.push(task);
+        let mut signatures: HashMap<String, String> = hashmap! {};
+        for (filename, signature_url) in signature_urls {
+            info!("Downloading signature {}", &signature_url);
+            let content = self.download_text(package, &signature_url).await?;
+            signatures.insert(filename, content);
         }
 
-        let downloaded = futures::future::join_all(tasks)
-            .await
-            .iter()
-            .map(|r| {
-                if let Err(e) = r {
-                    error!("Failed to download asset: {}", e);
-                }
-                r.as_ref()
-            })
-            .all(|r| r.is_ok());
+        Ok(Some(signatures))
+    }
 
-        if !downloaded {
-            return Err(anyhow!("Failed to download assets"));
+    /// Get the signature URLs of the downloaded artifacts of the package on the platform,
+    /// which are pairs of the artifact name and signature URL. None if no signature defined
+    fn get_signature_urls(
+        package: &Package,
+        version: &str,
+        release_asset_urls: &[String],
+        download_urls: &[String],
+        os: &str,
+        arch: &str,
+    ) -> anyhow::Result<Option<Vec<(String, String)>>> {
+        let package_signature = match package.target_of(os, arch)?.signature {
+            Some(package_signature) => package_signature,
+            None => return Ok(None),
+        };
+
+        let mut signature_urls: Vec<(String, String)> = vec![];
+        for download_url in download_urls {
+            let filename = download_url.split("/").last().unwrap_or_default();
+            let signature_name = package_signature
+                .signature_template()
+                .replace("{artifact}", filename)
+                .replace("{version}", version.trim_start_matches("v"))
+                .replace("{os}", os)
+                .replace("{arch}", arch);
+
+            let signature_url =
+                if Url::parse(&signature_name).is_ok() && signature_name.starts_with("https") {
+                    signature_name
+                } else if let Some(url) = package.source.resolve_url(&signature_name, version) {
+                    url
+                } else if let Some(url) = release_asset_urls
+                    .iter()
+                    .find(|it| it.split("/").last() == Some(signature_name.as_str()))
+                {
+                    url.clone()
+                } else {
+                    // verified when downloading the artifact, so fail only if the artifact is needed
+                    debug!("Signature {} of {} not found", signature_name, filename);
+                    continue;
+                };
+
+            signature_urls.push((filename.to_string(), signature_url));
         }
 
-        Ok(())
+        Ok(Some(signature_urls))
     }
 
     fn decompress_asset(
@@ -424,6 +1792,9 @@ impl ReleaseTrait for ReleaseService {
 
             debug!("Removing link {:?}", &current_pkg_dir);
             remove_symlink_dir(&current_pkg_dir)?;
+        } else if current_pkg_dir.symlink_metadata().is_ok() {
+            debug!("Removing dangling link {:?}", &current_pkg_dir);
+            remove_symlink_dir(&current_pkg_dir)?;
         }
 
         // remove it from index
@@ -459,7 +1830,7 @@ impl ReleaseTrait for ReleaseService {
             remove_symlink_file(exec_path)?;
         }
 
-        Ok(())
+        self.update_shim_indexes(pkg, vec![])
     }
 
     //noinspection ALL
@@ -473,49 +1844,38 @@ impl ReleaseTrait for ReleaseService {
 
         let pkg_dir = config.current_pkg_dir(pkg)?;
         let pkg_bin_dir = config.current_pkg_bin_dir(pkg)?;
-        let exec_mappings: HashMap<_, _> = pkg.target()?.executable_mappings.unwrap_or_default();
-
-        let semver_regex = Regex::new(
-            r"v?(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)
-(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?",
-        )?;
-
-        for dir in &[pkg_dir, pkg_bin_dir] {
-            debug!("Scanning executables in {:?}", dir);
 
-            if !dir.exists() {
-                debug!("Ignored scanning {:?}, because it does not exist", dir);
-                continue;
-            }
-
-            for entry in read_dir(dir)? {
-                let exec_path = entry?.path();
-                if !exec_path.is_executable() {
-                    debug!("Ignored non-executable {:?}", exec_path);
-                    continue;
-                } else if has_suffix(&trim_os_arch_version(
-                    &exec_path.file_name().unwrap().to_string_lossy(),
-                )) {
-                    debug!("Ignored executable {:?} due to suffix", exec_path);
-                    continue;
-                }
-
-                let mut exec_name = exec_path.file_name().unwrap().to_string_lossy().to_string();
-                exec_name = semver_regex.replace(&exec_name, "").to_string();
-                exec_name = exec_mappings
-                    .get(&exec_name)
-                    .unwrap_or(&exec_name)
-                    .to_string();
-
-                let exec_link = config.bin_dir()?.join(trim_os_arch_version(&exec_name));
-                if symlink {
-                    let _ = remove_file(&exec_link);
-                    symlink_file(&exec_path, &exec_link)?;
-                }
-                if exec_link.exists() {
-                    results.push(exec_link.to_string_lossy().to_string());
+        // link huber itself as the shims, which resolve the executables to run by the project versions
+        let shim_exec = if symlink && config.shim.enabled {
+            Some(env::current_exe()?)
+        } else {
+            None
+        };
+        let mut shims = vec![];
+
+        for (exec_name, exec_path) in scan_executables(pkg, &[pkg_dir, pkg_bin_dir])? {
+            let exec_link = config.bin_dir()?.join(&exec_name);
+            if symlink {
+                let _ = remove_file(&exec_link);
+
+                match &shim_exec {
+                    Some(shim_exec) => {
+                        symlink_file(shim_exec, &exec_link)?;
+                        shims.push(ShimIndex {
+                            executable: exec_name,
+                            package: pkg.clone(),
+                        });
+                    }
+                    None => symlink_file(&exec_path, &exec_link)?,
                 }
             }
+            if exec_link.exists() {
+                results.push(exec_link.to_string_lossy().to_string());
+            }
+        }
+
+        if !shims.is_empty() {
+            self.update_shim_indexes(pkg, shims)?;
         }
 
         Ok(results)
@@ -546,62 +1906,78 @@ impl ReleaseAsyncTrait for ReleaseService {
         package: &Package,
         package_github: &GithubPackage,
         prefer_stdlib: &PlatformStdLib,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Vec<ReleaseAsset>> {
         debug!("Downloading github package artifacts {}", &package);
 
-        let config = self.container.get::<Config>().unwrap();
-        let version = package.parse_version_from_tag_name(&package_github.tag_name)?;
+        // keep the release detail to download the assets via the API
+        let package = &Package {
+            detail: Some(PackageDetailType::Github {
+                package: package_github.clone(),
+            }),
+            ..package.clone()
+        };
+
+        let release_assets: Vec<(String, String)> = package_github
+            .assets
+            .iter()
+            .map(|it| {
+                decode(&it.browser_download_url).map(|url| (it.name.clone(), url.to_string()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        self.download_install_release_assets(
+            package,
+            &package_github.tag_name,
+            &release_assets,
+            prefer_stdlib,
+        )
+        .await
+    }
 
-        let mut asset_names = Self::get_assets(package, &version)?;
+    async fn download_install_gitlab_package(
+        &self,
+        package: &Package,
+        package_gitlab: &GitlabPackage,
+        prefer_stdlib: &PlatformStdLib,
+    ) -> anyhow::Result<Vec<ReleaseAsset>> {
+        debug!("Downloading gitlab package artifacts {}", &package);
 
-        let mut ext_asset_urls: Vec<String> = asset_names // external assets not on github
+        // prefer the permanent direct asset URL, which ends with the asset file path
+        let release_assets: Vec<(String, String)> = package_gitlab
+            .assets
             .iter()
-            .filter(|it| Url::parse(it).is_ok() && it.starts_with("https"))
-            .cloned()
+            .map(|it| {
+                let url = it.direct_asset_url.clone().unwrap_or(it.url.clone());
+                (it.name.clone(), url)
+            })
             .collect();
-        let mut asset_download_urls: Vec<String> = vec![];
-        asset_download_urls.append(&mut ext_asset_urls);
 
-        if !ext_asset_urls.is_empty() {
-            asset_names.retain(|it| !ext_asset_urls.contains(it));
-        }
+        self.download_install_release_assets(
+            package,
+            &package_gitlab.tag_name,
+            &release_assets,
+            prefer_stdlib,
+        )
+        .await
+    }
 
-        for asset in package_github.assets.iter() {
-            let asset_url = decode(&asset.browser_download_url)?.to_string();
+    async fn download_install_http_package(
+        &self,
+        package: &Package,
+        package_http: &HttpPackage,
+    ) -> anyhow::Result<Vec<ReleaseAsset>> {
+        debug!("Downloading http package artifacts {}", &package);
 
-            if asset_names.is_empty() {
-                debug!(
-                    "Checking {} if it's in the expected os/arch type: {}, since there are no expected artifact names defined",
-                    asset.name, asset_url
-                );
-                if !is_os_arch_match(
-                    env::consts::OS,
-                    env::consts::ARCH,
-                    &asset_url.to_lowercase(),
-                ) {
-                    continue;
-                }
-            } else {
-                debug!(
-                    "Checking {} if it's in the expected artifact names: {:?}",
-                    asset.name, asset_names
-                );
-                if !asset_names.contains(&asset.name)
-                    && !asset_names.iter().any(|it| asset_url.ends_with(it))
-                {
-                    debug!(
-                    "Ignored {}, not mentioned or not right arch type defined in the package artifact config",
-                    asset.name
-                );
-                    continue;
-                }
-            }
+        let config = self.container.get::<Config>().unwrap();
+        let version = package.parse_version_from_tag_name(&package_http.version)?;
 
-            debug!("Found asset: {}", asset_url);
-            asset_download_urls.push(asset_url.to_string());
-        }
+        let asset_names = Self::get_assets(package, &version, env::consts::OS, env::consts::ARCH)?;
+        let mut asset_download_urls: Vec<String> = asset_names
+            .iter()
+            .filter_map(|it| package.source.resolve_url(it, &version))
+            .collect();
 
-        if !package_github.assets.is_empty() && asset_download_urls.is_empty() {
+        if asset_download_urls.is_empty() {
             return Err(anyhow!(
                 "No available artifacts for {} to download. Expected artifact names: {:?}",
                 package.name,
@@ -609,121 +1985,25 @@ impl ReleaseAsyncTrait for ReleaseService {
             ));
         }
 
-        if prefer_stdlib != &PlatformStdLib::None {
-            info!(
-                "Prefer downloading assets for {} belonging to the specified stdlib: {}",
-                package.name,
-                prefer_stdlib
-            );
-
-            let stdlib_regex = Regex::new(&format!(
-                r"\b{}\b?",
-                prefer_stdlib.to_string().to_lowercase()
-            ))?;
-            let results: Vec<_> = asset_download_urls
-                .clone()
-                .into_iter()
-                .filter(|it| {
-                    let filename = Url::parse(it)
-                        .unwrap()
-                        .path_segments()
-                        .and_then(|segments| segments.last())
-                        .unwrap_or_default()
-                        .to_string();
-                    stdlib_regex.is_match(&filename)
-                })
-                .collect();
-            if !results.is_empty() {
-                asset_download_urls = results;
-            }
-        }
+        let checksums = self.download_checksums(package, &version, &[]).await?;
 
-        self.download_assets(package, config, &version, &mut asset_download_urls)
+        let signatures = self
+            .download_signatures(package, &version, &[], &asset_download_urls)
             .await?;
 
-        Ok(())
+        self.download_assets(
+            package,
+            config,
+            &version,
+            &mut asset_download_urls,
+            &checksums,
+            &signatures,
+        )
+        .await
     }
 
     async fn set_current(&self, release: &mut Release) -> anyhow::Result<Vec<String>> {
-        debug!("Setting the current release: {}", &release);
-
-        release.current = true;
-        release.name = release.package.name.clone();
-
-        debug!(
-            "Removing the old current release symbolic links: {}",
-            &release.package
-        );
-        self.reset_current(&release.package)?;
-
-        debug!("Updating the current release symbolic links: {}", &release);
-
-        let config = self.container.get::<Config>().unwrap();
-        let current_pkg_dir = config.current_pkg_dir(&release.package)?;
-        let source = config.installed_pkg_dir(&release.package, &release.version)?;
-
-        symlink_dir(&source, &current_pkg_dir)?;
-
-        let linked_exe_files = self.get_executables_for_current(&release.package, true)?;
-        if linked_exe_files.is_empty() {
-            return Err(anyhow!("No executables found when installing {}", &release));
-        }
-
-        // update old current release manifest
-        let index_f = config.current_index_file()?;
-        let mut indexes: Vec<ReleaseIndex> = vec![];
-
-        debug!("Updating the current index manifest: {:?}", &index_f);
-
-        if index_f.exists() {
-            let f = File::open(&index_f)?;
-            indexes = serde_yaml::from_reader(&f)?;
-
-            if let Some(found) = indexes.iter().find(|it| it.name == release.package.name) {
-                let old_pkg_manifest_path =
-                    config.installed_pkg_manifest_file(&release.package, &found.version)?;
-                let f = File::open(&old_pkg_manifest_path)?;
-
-                let mut r: Release = serde_yaml::from_reader(f)?;
-                r.current = false;
-
-                let _ = remove_file(&old_pkg_manifest_path);
-                let f = File::create(&old_pkg_manifest_path)?;
-                serde_yaml::to_writer(f, &r)?;
-            }
-
-            indexes.retain(|it| it.name != release.package.name);
-        }
-
-        indexes.push(ReleaseIndex {
-            name: release.package.name.clone(),
-            version: release.version.clone(),
-            owner: release.package.source.owner().to_string(),
-            source: release.package.source.to_string(),
-        });
-
-        // update current release index file
-        let _ = remove_file(&index_f);
-        let index_f = File::create(&index_f)?;
-        serde_yaml::to_writer(index_f, &indexes)?;
-
-        let release_f = config.installed_pkg_manifest_file(&release.package, &release.version)?;
-        let _ = remove_file(&release_f);
-        let release_f = File::create(release_f)?;
-        serde_yaml::to_writer(release_f, &release)?;
-
-        // clean other installed releases as non-current
-        let releases = self.find(&release.package).await?;
-        let inactive_releases: Vec<&Release> = releases
-            .iter()
-            .filter(|it| it.version != release.version)
-            .collect();
-
-        for r in inactive_releases {
-            self.clean_current(r)?;
-        }
-
-        Ok(linked_exe_files)
+        self.switch_current(release, true).await
     }
 }
 
@@ -739,10 +2019,17 @@ impl ItemOperationTrait for ReleaseService {
         let pkg_service = self.container.get::<PackageService>().unwrap();
         let release_service = self.container.get::<ReleaseService>().unwrap();
 
+        // the package may have no current release, if installed only by `huber run`
         let pkg = pkg_service.get(name)?;
-        let release = release_service.current(&pkg)?;
+        if let Ok(release) = release_service.current(&pkg) {
+            self.reset_current(&release.package)?;
+        }
+        self.unlink_versioned_executables(&pkg)?;
 
-        self.reset_current(&release.package)?;
+        let history_f = config.current_history_file(&pkg)?;
+        if history_f.exists() {
+            remove_file(history_f)?;
+        }
 
         let dir = config.installed_pkg_base_dir(&pkg)?;
         Ok(remove_dir_all(dir)?)
@@ -810,7 +2097,7 @@ impl ItemOperationAsyncTrait for ReleaseService {
             return Err(anyhow!("{} already installed", &obj.name));
         }
 
-        self.update(&obj, &PlatformStdLib::None, true).await
+        self.update(&obj, &PlatformStdLib::None, true, true).await
     }
 
     async fn update(&self, _obj: &Self::Item_) -> anyhow::Result<Self::ItemInstance_> {
@@ -882,3 +2169,204 @@ impl ItemSearchTrait for ReleaseService {
         Ok(found_items)
     }
 }
+
+// scan the executables in the directories, mapped from the names linked in the bin directory
+fn scan_executables(pkg: &Package, dirs: &[PathBuf]) -> anyhow::Result<Vec<(String, PathBuf)>> {
+    let mut results = vec![];
+    let exec_mappings: HashMap<_, _> = pkg.target()?.executable_mappings.unwrap_or_default();
+
+    let semver_regex = Regex::new(
+        r"v?(0|[1-9]\d*)\.(0|[1-9]\d*)\.(0|[1-9]\d*)(?:-((?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*)
+(?:\.(?:0|[1-9]\d*|\d*[a-zA-Z-][0-9a-zA-Z-]*))*))?(?:\+([0-9a-zA-Z-]+(?:\.[0-9a-zA-Z-]+)*))?",
+    )?;
+
+    for dir in dirs {
+        debug!("Scanning executables in {:?}", dir);
+
+        if !dir.exists() {
+            debug!("Ignored scanning {:?}, because it does not exist", dir);
+            continue;
+        }
+
+        for entry in read_dir(dir)? {
+            let exec_path = entry?.path();
+            if !exec_path.is_executable() {
+                debug!("Ignored non-executable {:?}", exec_path);
+                continue;
+            } else if has_suffix(&trim_os_arch_version(
+                &exec_path.file_name().unwrap().to_string_lossy(),
+            )) {
+                debug!("Ignored executable {:?} due to suffix", exec_path);
+                continue;
+            }
+
+            let mut exec_name = exec_path.file_name().unwrap().to_string_lossy().to_string();
+            exec_name = semver_regex.replace(&exec_name, "").to_string();
+            exec_name = exec_mappings
+                .get(&exec_name)
+                .unwrap_or(&exec_name)
+                .to_string();
+
+            results.push((trim_os_arch_version(&exec_name), exec_path));
+        }
+    }
+
+    Ok(results)
+}
+
+// the version suffix of the versioned executables, the major and minor versions of semantic versions (e.g. 1.29),
+// or the version without the leading 'v' otherwise
+fn get_version_suffix(version: &str) -> String {
+    let version = version.trim_start_matches('v');
+
+    match Version::parse(version) {
+        Ok(it) => format!("{}.{}", it.major, it.minor),
+        Err(_) => version.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use simpledi_rs::di::DIContainerTrait;
+    use simpledi_rs::{create_dep, inject_dep};
+
+    use super::*;
+    use crate::model::release::{tool_release, ReleaseKind};
+
+    fn init_container(config: &Config) -> Arc<DIContainer> {
+        let mut container = DIContainer::new();
+        create_dep!(config.clone(), container);
+        create_dep!(ReleaseService::new(), container);
+        let container = container.init().unwrap();
+        inject_dep!(ReleaseService, container.clone());
+
+        container
+    }
+
+    fn stage(config: &Config, release: &Release, executable: bool) -> PathBuf {
+        let staging_dir =
+            ReleaseService::clean_staging_dir(config, &release.package, &release.version).unwrap();
+        if executable {
+            let exec_file = staging_dir.join("tool");
+            fs::write(&exec_file, "#!/bin/sh").unwrap();
+            set_executable_permission(&exec_file).unwrap();
+        }
+
+        staging_dir
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn test_install_staged_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            huber_dir: dir.path().to_path_buf(),
+            ..Default::default()
+        };
+
+        let container = init_container(&config);
+        let release_service = container.get::<ReleaseService>().unwrap();
+
+        let mut installed_release = tool_release("v1.0.0", ReleaseKind::Release);
+        let staging_dir = stage(&config, &installed_release, true);
+        release_service
+            .install_staged_release(&mut installed_release, &staging_dir, true)
+            .await
+            .unwrap();
+        assert!(!staging_dir.exists());
+
+        // no executables found in the new release
+        let mut failed_release = tool_release("v2.0.0", ReleaseKind::Release);
+        let staging_dir = stage(&config, &failed_release, false);
+        assert!(release_service
+            .install_staged_release(&mut failed_release, &staging_dir, true)
+            .await
+            .is_err());
+
+        let pkg = &installed_release.package;
+        assert!(!config
+            .installed_pkg_base_dir(pkg)
+            .unwrap()
+            .join("v2.0.0")
+            .exists());
+        assert_eq!(release_service.current(pkg).unwrap().version, "v1.0.0");
+        let indexes: Vec<ReleaseIndex> =
+            serde_yaml::from_reader(File::open(config.current_index_file().unwrap()).unwrap())
+                .unwrap();
+        assert_eq!(indexes.len(), 1);
+        assert_eq!(indexes[0].version, "v1.0.0");
+        assert!(config.bin_dir().unwrap().join("tool").exists());
+
+        // installed without setting it as current
+        let mut ephemeral_release = tool_release("v3.0.0", ReleaseKind::Release);
+        let staging_dir = stage(&config, &ephemeral_release, true);
+        release_service
+            .install_staged_release(&mut ephemeral_release, &staging_dir, false)
+            .await
+            .unwrap();
+        assert!(config
+            .installed_pkg_manifest_file(pkg, "v3.0.0")
+            .unwrap()
+            .exists());
+        assert!(!ephemeral_release.current);
+        assert_eq!(release_service.current(pkg).unwrap().version, "v1.0.0");
+        assert_eq!(release_service.find(pkg).await.unwrap().len(), 2);
+
+        let link_dir = config.installed_pkg_link_dir(pkg, "v3.0.0").unwrap();
+        assert!(link_dir.join("tool").exists());
+        assert_eq!(
+            release_service.get_executable_dirs(pkg, "v3.0.0").unwrap(),
+            vec![link_dir]
+        );
+    }
+
+    #[test]
+    fn test_get_version_suffix() {
+        assert_eq!(get_version_suffix("v1.29.3"), "1.29");
+        assert_eq!(get_version_suffix("1.30.0-rc.1"), "1.30");
+        assert_eq!(get_version_suffix("nightly-20240101"), "nightly-20240101");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn test_link_versioned_executables() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            huber_dir: dir.path().to_path_buf(),
+            versioned_executables: vec!["tool".to_string()],
+            ..Default::default()
+        };
+
+        let container = init_container(&config);
+        let release_service = container.get::<ReleaseService>().unwrap();
+
+        for version in ["v1.29.3", "v1.30.0", "v1.29.1"] {
+            let mut release = tool_release(version, ReleaseKind::Release);
+            let staging_dir = stage(&config, &release, true);
+            release_service
+                .install_staged_release(&mut release, &staging_dir, true)
+                .await
+                .unwrap();
+        }
+
+        let pkg = &tool_release("v1.29.1", ReleaseKind::Release).package;
+        let bin_dir = config.bin_dir().unwrap();
+        let pkg_base_dir = config.installed_pkg_base_dir(pkg).unwrap();
+
+        // the higher version of the same suffix is linked
+        assert_eq!(
+            bin_dir.join("tool-1.29").read_link().unwrap(),
+            pkg_base_dir.join("v1.29.3").join("tool")
+        );
+        assert_eq!(
+            bin_dir.join("tool-1.30").read_link().unwrap(),
+            pkg_base_dir.join("v1.30.0").join("tool")
+        );
+        assert_eq!(release_service.current(pkg).unwrap().version, "v1.29.1");
+
+        release_service.unlink_versioned_executables(pkg).unwrap();
+        assert!(bin_dir.join("tool-1.29").symlink_metadata().is_err());
+        assert!(bin_dir.join("tool-1.30").symlink_metadata().is_err());
+        assert!(bin_dir.join("tool").exists());
+    }
+}
@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use log::debug;
 use simpledi_rs::di::{DIContainer, DIContainerExtTrait, DependencyInjectTrait};
 
+use crate::error::HuberError::OfflineUnsupported;
 use crate::model::config::{Config, ConfigPath};
 use crate::model::package::Package;
 use crate::model::repo::{Repository, LOCAL_REPO};
@@ -98,34 +99,9 @@ impl ItemOperationTrait for RepoService {
         Ok(())
     }
 
-    // FIXME enhance performance
     fn list(&self) -> anyhow::Result<Vec<Self::ItemInstance>> {
         let config = self.container.get::<Config>().unwrap();
-
-        let mut repos: Vec<Repository> = vec![];
-        let path = config.repo_root_dir()?;
-
-        for entry in read_dir(&path)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                let dir_name = path.file_name().unwrap().to_str().unwrap();
-                // not include managed repo
-                if dir_name == "huber" {
-                    continue;
-                }
-
-                let repo_f = config.external_repo_file(dir_name)?;
-                if repo_f.exists() {
-                    let f = File::open(&repo_f)?;
-                    let result: Repository = serde_yaml::from_reader(f)?;
-                    repos.push(result);
-                }
-            }
-        }
-
-        Ok(repos)
+        list_repositories(config)
     }
 
     fn get(&self, _name: &str) -> anyhow::Result<Self::ItemInstance> {
@@ -144,6 +120,12 @@ impl ItemOperationAsyncTrait for RepoService {
 
         debug!("Creating external repo: {:?}", &obj);
         match &obj {
+            _ if obj.url.is_some() && config.offline => {
+                return Err(anyhow!(OfflineUnsupported(
+                    "adding a repository by URL".to_string()
+                )));
+            }
+
             _ if obj.url.is_some() => {
                 self.download_save_pkgs_file_from_remote_github(
                     &obj.name,
@@ -292,6 +274,35 @@ impl RepoAsyncTrait for RepoService {
     }
 }
 
+// FIXME enhance performance
+/// List the external repositories, not including the managed repository
+pub fn list_repositories(config: &Config) -> anyhow::Result<Vec<Repository>> {
+    let mut repos: Vec<Repository> = vec![];
+    let path = config.repo_root_dir()?;
+
+    for entry in read_dir(&path)? {
+        let entry = entry?;
+        let path = entry.path();
+
+        if path.is_dir() {
+            let dir_name = path.file_name().unwrap().to_str().unwrap();
+            // not include managed repo
+            if dir_name == "huber" {
+                continue;
+            }
+
+            let repo_f = config.external_repo_file(dir_name)?;
+            if repo_f.exists() {
+                let f = File::open(&repo_f)?;
+                let result: Repository = serde_yaml::from_reader(f)?;
+                repos.push(result);
+            }
+        }
+    }
+
+    Ok(repos)
+}
+
 fn get_packages_from_file(f: File) -> anyhow::Result<Vec<Package>> {
     Ok(serde_yaml::from_reader(&f)?)
 }
@@ -85,6 +85,7 @@ impl UpdateTrait for HuberUpdateService {
         let _ = remove_dir_all(config.installed_pkg_root_dir()?);
         let _ = remove_dir_all(config.temp_dir()?);
         let _ = remove_dir_all(config.repo_root_dir()?);
+        let _ = remove_dir_all(config.metadata_dir()?);
         let _ = remove_file(config.lock_file()?);
         let _ = remove_file(config.config_file()?);
 
@@ -127,7 +128,9 @@ impl UpdateAsyncTrait for HuberUpdateService {
         let release = release_service.get_latest(&pkg).await?;
         pkg.version = Some(release.version);
 
-        release_service.update(&pkg, prefer_stdlib, true).await?;
+        release_service
+            .update(&pkg, prefer_stdlib, true, true)
+            .await?;
         Ok(())
     }
 }
@@ -0,0 +1,538 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use log::{debug, info};
+use simpledi_rs::di::{DIContainer, DIContainerTrait, DependencyInjectTrait};
+use simpledi_rs::{create_dep, inject_dep};
+
+use crate::error::HuberError::ProjectVersionNotInstalled;
+use crate::lock_huber_ops;
+use crate::log::Logger;
+use crate::model::config::{Config, ConfigPath};
+use crate::model::package::Package;
+use crate::model::release::Release;
+use crate::semver::{find_max_satisfied_version, parse_version_req};
+use crate::service::release::{ReleaseService, ReleaseTrait};
+use crate::service::ItemOperationAsyncTrait;
+
+pub const HUBER_VERSION_FILE: &str = ".huber-version";
+pub const TOOL_VERSIONS_FILE: &str = ".tool-versions";
+// the version of .tool-versions to run the executable outside asdf, regarded as the current version
+const SYSTEM_VERSION: &str = "system";
+
+// versions of a package required by a project version file, the former ones preferred
+#[derive(Debug, PartialEq)]
+pub struct ProjectVersion {
+    pub file: PathBuf,
+    pub versions: Vec<String>,
+}
+
+/// Get the path of the shim if huber is invoked via a shim linked in the bin directory
+pub fn get_shim_path() -> Option<PathBuf> {
+    let arg0 = PathBuf::from(env::args_os().next()?);
+    if get_shim_name(&arg0)? == env!("CARGO_PKG_NAME") {
+        return None;
+    }
+
+    // invoked by the executable name, the first one found in PATH is the executed one
+    let path = if arg0.components().count() > 1 {
+        arg0
+    } else {
+        env::split_paths(&env::var_os("PATH")?)
+            .map(|it| it.join(&arg0))
+            .find(|it| it.exists())?
+    };
+
+    is_shim(&path).then_some(path)
+}
+
+// the shim is a link in the bin directory of the huber directory, and indexed in the shim index file.
+// Other links to huber (e.g. aliases) are not shims
+fn is_shim(path: &Path) -> bool {
+    if !path.is_symlink() {
+        return false;
+    }
+
+    let (Some(name), Some(bin_dir)) = (
+        get_shim_name(path),
+        path.parent().and_then(|it| it.canonicalize().ok()),
+    ) else {
+        return false;
+    };
+    let Some(huber_dir) = bin_dir.parent() else {
+        return false;
+    };
+
+    // check the packages directory first, not to create it for the link outside huber directories
+    if !huber_dir.join("packages").is_dir() {
+        return false;
+    }
+    let Ok(config) = Config::from_huber_dir(huber_dir) else {
+        return false;
+    };
+    if config.bin_dir().ok().as_ref() != Some(&bin_dir) {
+        return false;
+    }
+
+    let container = init_shim_services(&config);
+    let release_service = container.get::<ReleaseService>().unwrap();
+    release_service
+        .get_shim_indexes()
+        .map(|indexes| indexes.iter().any(|it| it.executable == name))
+        .unwrap_or(false)
+}
+
+// the executable name of the shim, only without the .exe extension on Windows,
+// because dots are common in executable names (e.g. kubectl-1.29)
+fn get_shim_name(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+
+    if cfg!(target_os = "windows") {
+        if let Some(name) = name.strip_suffix(".exe") {
+            return Some(name.to_string());
+        }
+    }
+
+    Some(name)
+}
+
+/// Run the executable of the shim in the version required by the nearest project version file,
+/// or the current version otherwise. The exit code of the executable is returned
+pub async fn run_shim(shim_path: &Path) -> anyhow::Result<i32> {
+    let name =
+        get_shim_name(shim_path).ok_or_else(|| anyhow!("Invalid shim path: {:?}", shim_path))?;
+
+    // the shims are linked in the bin directory of the huber directory
+    let bin_dir = shim_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .canonicalize()?;
+    let huber_dir = bin_dir
+        .parent()
+        .ok_or_else(|| anyhow!("Invalid shim path: {:?}", shim_path))?;
+
+    let config = Config::from_huber_dir(huber_dir)?;
+    Logger::init(&config)?;
+
+    let container = init_shim_services(&config);
+    let exec_path =
+        resolve_shim_executable(&config, &container, &name, &env::current_dir()?).await?;
+    debug!("Running {:?} via the shim {:?}", exec_path, shim_path);
+
+    let mut args = env::args_os();
+    let arg0 = args.next().unwrap();
+    let mut command = Command::new(&exec_path);
+    command.args(args);
+
+    exec(command, arg0)
+}
+
+// only the release service is required by shims, instead of initializing all services with the repositories
+fn init_shim_services(config: &Config) -> Arc<DIContainer> {
+    let mut container = DIContainer::new();
+    create_dep!(config.clone(), container);
+    create_dep!(ReleaseService::new(), container);
+
+    let container = container.init().unwrap();
+    inject_dep!(ReleaseService, container.clone());
+
+    container
+}
+
+/// Resolve the executable of the shim in the directory
+pub async fn resolve_shim_executable(
+    config: &Config,
+    container: &DIContainer,
+    name: &str,
+    dir: &Path,
+) -> anyhow::Result<PathBuf> {
+    let release_service = container.get::<ReleaseService>().unwrap();
+
+    let shim = release_service
+        .get_shim_indexes()?
+        .into_iter()
+        .find(|it| it.executable == name)
+        .ok_or_else(|| anyhow!("No package found for the shim {}", name))?;
+    let pkg = &shim.package;
+
+    let release = match find_project_version(dir, &pkg.name)? {
+        Some(project_version) => {
+            resolve_project_release(config, release_service, pkg, &project_version).await?
+        }
+        None => None,
+    };
+    let release = match release {
+        Some(release) => release,
+        None => release_service.current(pkg)?,
+    };
+
+    release_service
+        .get_executables(&release.package, &release.version)?
+        .remove(name)
+        .ok_or_else(|| anyhow!("Executable {} not found in {}", name, release))
+}
+
+/// Find the versions of the package required by the nearest project version file, from the directory
+/// to its ancestors. .huber-version takes precedence over .tool-versions in the same directory
+pub fn find_project_version(dir: &Path, pkg_name: &str) -> anyhow::Result<Option<ProjectVersion>> {
+    for dir in dir.ancestors() {
+        for file_name in [HUBER_VERSION_FILE, TOOL_VERSIONS_FILE] {
+            let file = dir.join(file_name);
+            if !file.is_file() {
+                continue;
+            }
+
+            if let Some(versions) =
+                parse_project_versions(file_name, &read_to_string(&file)?, pkg_name)
+            {
+                return Ok(Some(ProjectVersion { file, versions }));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+// .huber-version lists 'package@version', and .tool-versions lists 'package version [fallback versions]' per line
+fn parse_project_versions(file_name: &str, content: &str, pkg_name: &str) -> Option<Vec<String>> {
+    content.lines().find_map(|line| {
+        let line = line.split('#').next().unwrap_or_default().trim();
+
+        let (name, versions) = if file_name == HUBER_VERSION_FILE {
+            let (name, version) = line.split_once('@')?;
+            (name.trim(), vec![version.trim().to_string()])
+        } else {
+            let mut fields = line.split_whitespace();
+            (fields.next()?, fields.map(String::from).collect())
+        };
+
+        (name == pkg_name && !versions.is_empty() && versions.iter().all(|it| !it.is_empty()))
+            .then_some(versions)
+    })
+}
+
+/// Find the installed release of the project versions in order,
+/// or None if the current release is required by 'system' of .tool-versions
+pub fn find_project_release(
+    pkg: &Package,
+    project_version: &ProjectVersion,
+    releases: Vec<Release>,
+) -> anyhow::Result<Option<Release>> {
+    let versions: Vec<_> = releases.iter().map(|it| it.version.clone()).collect();
+
+    for version in &project_version.versions {
+        if version == SYSTEM_VERSION {
+            return Ok(None);
+        }
+
+        if let Some(version) = match_version(version, &versions) {
+            return Ok(releases.into_iter().find(|it| it.version == version));
+        }
+    }
+
+    Err(anyhow!(ProjectVersionNotInstalled(
+        format!("{}@{}", pkg.name, project_version.versions[0]),
+        project_version.file.clone()
+    )))
+}
+
+// resolve the installed release of the project versions, or None to run the current release
+async fn resolve_project_release(
+    config: &Config,
+    release_service: &ReleaseService,
+    pkg: &Package,
+    project_version: &ProjectVersion,
+) -> anyhow::Result<Option<Release>> {
+    debug!(
+        "Found the versions {:?} of {} in {:?}",
+        project_version.versions, pkg.name, project_version.file
+    );
+
+    let releases = release_service.find(pkg).await?;
+    match find_project_release(pkg, project_version, releases) {
+        Err(_) if config.shim.auto_install => {
+            install_project_release(config, release_service, pkg, &project_version.versions[0])
+                .await
+                .map(Some)
+        }
+        Err(e) => Err(anyhow!(
+            "{}; Install it, or enable shim.auto_install in the config",
+            e
+        )),
+        result => result,
+    }
+}
+
+// install the version via huber, then restore the current release switched by the installation
+async fn install_project_release(
+    config: &Config,
+    release_service: &ReleaseService,
+    pkg: &Package,
+    version: &str,
+) -> anyhow::Result<Release> {
+    let current_release = release_service.current(pkg).ok();
+
+    info!(
+        "Installing {}@{} required by the project",
+        pkg.name, version
+    );
+    let status = Command::new(env::current_exe()?)
+        .arg("--huber-dir")
+        .arg(&config.huber_dir)
+        .arg("install")
+        .arg(format!("{}@{}", pkg.name, version))
+        .status()?;
+    if !status.success() {
+        return Err(anyhow!("Failed to install {}@{}", pkg.name, version));
+    }
+
+    let installed_release = release_service.current(pkg)?;
+    if let Some(release) = current_release.filter(|it| it.version != installed_release.version) {
+        lock_huber_ops!(config);
+        release_service.restore_current(&release).await?;
+    }
+
+    Ok(installed_release)
+}
+
+/// Match the version or the version requirement with the versions, the leading 'v' of versions is ignored
+pub fn match_version(version: &str, versions: &[String]) -> Option<String> {
+    if let Some(requirement) = parse_version_req(version) {
+        return find_max_satisfied_version(&requirement, versions.iter().cloned());
+    }
+
+    versions
+        .iter()
+        .find(|it| it.trim_start_matches('v') == version.trim_start_matches('v'))
+        .cloned()
+}
+
+/// Replace the current process with the command, so the exit code and signals are passed through.
+/// On Windows, the command is run as a child process, and its exit code is returned
+#[cfg(not(target_os = "windows"))]
+pub fn exec(mut command: Command, arg0: OsString) -> anyhow::Result<i32> {
+    use std::os::unix::process::CommandExt;
+
+    // only returns if failed to replace the process
+    let err = command.arg0(arg0).exec();
+    Err(anyhow!(
+        "Failed to run {:?}: {}",
+        command.get_program(),
+        err
+    ))
+}
+
+#[cfg(target_os = "windows")]
+pub fn exec(mut command: Command, _arg0: OsString) -> anyhow::Result<i32> {
+    let status = command.status()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+    use std::fs::{create_dir_all, File};
+
+    use super::*;
+    use crate::fs::set_executable_permission;
+    use crate::model::config::ShimConfig;
+    use crate::model::release::{tool_release, ReleaseKind};
+
+    fn install(config: &Config, version: &str) -> Release {
+        let release = tool_release(version, ReleaseKind::Release);
+
+        let pkg_dir = config
+            .installed_pkg_dir(&release.package, &release.version)
+            .unwrap();
+        let exec_file = pkg_dir.join("tool");
+        fs::write(&exec_file, "#!/bin/sh").unwrap();
+        set_executable_permission(&exec_file).unwrap();
+
+        let manifest_file = config
+            .installed_pkg_manifest_file(&release.package, &release.version)
+            .unwrap();
+        serde_yaml::to_writer(File::create(manifest_file).unwrap(), &release).unwrap();
+
+        release
+    }
+
+    #[test]
+    fn test_parse_project_versions() {
+        let content = "# comment\nother@1.0.0\ntool@~1.2 # pinned\n";
+        assert_eq!(
+            parse_project_versions(HUBER_VERSION_FILE, content, "tool"),
+            Some(vec!["~1.2".to_string()])
+        );
+        assert_eq!(
+            parse_project_versions(HUBER_VERSION_FILE, content, "missing"),
+            None
+        );
+
+        let content = "other 1.0.0\ntool 1.2.3 system\n";
+        assert_eq!(
+            parse_project_versions(TOOL_VERSIONS_FILE, content, "tool"),
+            Some(vec!["1.2.3".to_string(), "system".to_string()])
+        );
+        assert_eq!(
+            parse_project_versions(TOOL_VERSIONS_FILE, "tool\n", "tool"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_project_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let project_dir = dir.path().join("project");
+        let nested_dir = project_dir.join("src").join("nested");
+        create_dir_all(&nested_dir).unwrap();
+
+        fs::write(
+            dir.path().join(TOOL_VERSIONS_FILE),
+            "tool 1.0.0\nother 2.0.0\n",
+        )
+        .unwrap();
+        fs::write(project_dir.join(HUBER_VERSION_FILE), "tool@v1.1.0\n").unwrap();
+        fs::write(project_dir.join(TOOL_VERSIONS_FILE), "tool 1.2.0\n").unwrap();
+
+        assert_eq!(
+            find_project_version(&nested_dir, "tool").unwrap(),
+            Some(ProjectVersion {
+                file: project_dir.join(HUBER_VERSION_FILE),
+                versions: vec!["v1.1.0".to_string()],
+            })
+        );
+        assert_eq!(
+            find_project_version(&nested_dir, "other").unwrap(),
+            Some(ProjectVersion {
+                file: dir.path().join(TOOL_VERSIONS_FILE),
+                versions: vec!["2.0.0".to_string()],
+            })
+        );
+        assert_eq!(find_project_version(dir.path(), "missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_shim_name() {
+        assert_eq!(
+            get_shim_name(Path::new("/huber/bin/kubectl-1.29")),
+            Some("kubectl-1.29".to_string())
+        );
+        assert_eq!(
+            get_shim_name(Path::new("tool.exe")),
+            Some(
+                if cfg!(target_os = "windows") {
+                    "tool"
+                } else {
+                    "tool.exe"
+                }
+                .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_match_version() {
+        let versions = vec![
+            "v1.28.5".to_string(),
+            "v1.29.3".to_string(),
+            "nightly".to_string(),
+        ];
+
+        assert_eq!(
+            match_version("1.28.5", &versions),
+            Some("v1.28.5".to_string())
+        );
+        assert_eq!(
+            match_version("v1.29.3", &versions),
+            Some("v1.29.3".to_string())
+        );
+        assert_eq!(
+            match_version("~1.29", &versions),
+            Some("v1.29.3".to_string())
+        );
+        assert_eq!(
+            match_version("nightly", &versions),
+            Some("nightly".to_string())
+        );
+        assert_eq!(match_version("1.30.0", &versions), None);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn test_resolve_shim_executable() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config {
+            huber_dir: dir.path().join("huber"),
+            shim: ShimConfig {
+                enabled: true,
+                auto_install: false,
+            },
+            ..Default::default()
+        };
+        let container = init_shim_services(&config);
+        let release_service = container.get::<ReleaseService>().unwrap();
+
+        let old_release = install(&config, "v1.0.0");
+        let current_release = install(&config, "v2.0.0");
+        release_service
+            .restore_current(&current_release)
+            .await
+            .unwrap();
+
+        let shim_indexes = release_service.get_shim_indexes().unwrap();
+        assert_eq!(shim_indexes.len(), 1);
+        assert_eq!(shim_indexes[0].executable, "tool");
+
+        // only the indexed links in the bin directory are shims, not aliases of huber
+        let bin_dir = config.bin_dir().unwrap();
+        assert!(is_shim(&bin_dir.join("tool")));
+        let alias_link = bin_dir.join("hb");
+        std::os::unix::fs::symlink(env::current_exe().unwrap(), &alias_link).unwrap();
+        assert!(!is_shim(&alias_link));
+        let outside_bin_dir = dir.path().join("bin");
+        create_dir_all(&outside_bin_dir).unwrap();
+        let outside_link = outside_bin_dir.join("tool");
+        std::os::unix::fs::symlink(env::current_exe().unwrap(), &outside_link).unwrap();
+        assert!(!is_shim(&outside_link));
+        assert!(!dir.path().join("packages").exists());
+
+        let project_dir = dir.path().join("project");
+        create_dir_all(&project_dir).unwrap();
+
+        // no project version file
+        let exec_path = resolve_shim_executable(&config, &container, "tool", &project_dir)
+            .await
+            .unwrap();
+        assert_eq!(
+            exec_path,
+            config
+                .installed_pkg_dir(&current_release.package, &current_release.version)
+                .unwrap()
+                .join("tool")
+        );
+
+        fs::write(project_dir.join(TOOL_VERSIONS_FILE), "tool 1.0.0\n").unwrap();
+        let exec_path = resolve_shim_executable(&config, &container, "tool", &project_dir)
+            .await
+            .unwrap();
+        assert_eq!(
+            exec_path,
+            config
+                .installed_pkg_dir(&old_release.package, &old_release.version)
+                .unwrap()
+                .join("tool")
+        );
+
+        fs::write(project_dir.join(HUBER_VERSION_FILE), "tool@v3.0.0\n").unwrap();
+        assert!(
+            resolve_shim_executable(&config, &container, "tool", &project_dir)
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+use base64::prelude::*;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use p256::pkcs8::DecodePublicKey;
+
+use crate::error::HuberError;
+use crate::model::package::PackageSignature;
+
+/// Verify the file against the signature by the trusted public key of the package.
+/// Any failure is regarded as a verification failure, so the artifact will not be installed.
+pub fn verify_signature(
+    signature: &PackageSignature,
+    path: &Path,
+    artifact: &str,
+    signature_content: &str,
+) -> anyhow::Result<()> {
+    let data = fs::read(path)?;
+
+    match signature {
+        PackageSignature::Cosign { public_key, .. } => {
+            verify_cosign_signature(&data, public_key, signature_content)
+        }
+        PackageSignature::Minisign { public_key, .. } => {
+            verify_minisign_signature(&data, public_key, signature_content)
+        }
+    }
+    .map_err(|e| {
+        anyhow!(HuberError::SignatureVerificationFailed(
+            artifact.to_string(),
+            e
+        ))
+    })
+}
+
+fn verify_cosign_signature(data: &[u8], public_key: &str, signature: &str) -> anyhow::Result<()> {
+    // cosign outputs the public key either in PEM or base64 encoded PEM
+    let public_key = if public_key.trim_start().starts_with("-----BEGIN") {
+        public_key.to_string()
+    } else {
+        String::from_utf8(BASE64_STANDARD.decode(public_key.trim())?)?
+    };
+
+    // keyless signatures are verified by the certificate identity and the transparency log, which needs network
+    if public_key
+        .trim_start()
+        .starts_with("-----BEGIN CERTIFICATE")
+    {
+        return Err(anyhow!(
+            "Keyless cosign verification by certificates is not supported, use the public key of the signer"
+        ));
+    }
+    if signature.trim_start().starts_with('{') {
+        return Err(anyhow!(
+            "Keyless cosign bundles are not supported, use the signature signed by the public key"
+        ));
+    }
+
+    let verifying_key =
+        VerifyingKey::from_public_key_pem(public_key.trim()).map_err(|e| anyhow!("{}", e))?;
+    let signature = BASE64_STANDARD.decode(signature.trim())?;
+    let signature = Signature::from_der(&signature).map_err(|e| anyhow!("{}", e))?;
+
+    verifying_key
+        .verify(data, &signature)
+        .map_err(|e| anyhow!("{}", e))
+}
+
+fn verify_minisign_signature(data: &[u8], public_key: &str, signature: &str) -> anyhow::Result<()> {
+    // the public key can be the key itself or the content of the public key file
+    let public_key = if public_key.trim().contains('\n') {
+        minisign_verify::PublicKey::decode(public_key.trim())?
+    } else {
+        minisign_verify::PublicKey::from_base64(public_key.trim())?
+    };
+    let signature = minisign_verify::Signature::decode(signature.trim())?;
+
+    Ok(public_key.verify(data, &signature, false)?)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    use p256::ecdsa::signature::Signer;
+    use p256::ecdsa::SigningKey;
+    use p256::pkcs8::{EncodePublicKey, LineEnding};
+
+    use super::*;
+
+    const MINISIGN_PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const MINISIGN_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+    fn create_file(dir: &Path, content: &[u8]) -> PathBuf {
+        let path = dir.join("pkg");
+        File::create(&path).unwrap().write_all(content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_verify_cosign_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = create_file(dir.path(), b"test");
+
+        let signing_key = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+        let public_key = signing_key
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        let signature: Signature = signing_key.sign(b"test");
+        let signature = BASE64_STANDARD.encode(signature.to_der().as_bytes());
+
+        let package_signature = PackageSignature::Cosign {
+            signature_template: "{artifact}.sig".to_string(),
+            public_key: public_key.clone(),
+        };
+        assert!(verify_signature(&package_signature, &path, "pkg", &signature).is_ok());
+
+        let package_signature = PackageSignature::Cosign {
+            signature_template: "{artifact}.sig".to_string(),
+            public_key: BASE64_STANDARD.encode(&public_key),
+        };
+        assert!(verify_signature(&package_signature, &path, "pkg", &signature).is_ok());
+
+        let err =
+            verify_signature(&package_signature, &path, "pkg", r#"{"mediaType": ""}"#).unwrap_err();
+        assert!(format!("{:?}", err).contains("not supported"));
+
+        let path = create_file(dir.path(), b"tampered");
+        let err = verify_signature(&package_signature, &path, "pkg", &signature).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<HuberError>(),
+            Some(HuberError::SignatureVerificationFailed(..))
+        ));
+
+        let package_signature = PackageSignature::Cosign {
+            signature_template: "{artifact}.sig".to_string(),
+            public_key: "-----BEGIN CERTIFICATE-----\n-----END CERTIFICATE-----".to_string(),
+        };
+        let err = verify_signature(&package_signature, &path, "pkg", &signature).unwrap_err();
+        assert!(format!("{:?}", err).contains("not supported"));
+    }
+
+    #[test]
+    fn test_verify_minisign_signature() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = create_file(dir.path(), b"test");
+
+        let package_signature = PackageSignature::Minisign {
+            signature_template: "{artifact}.minisig".to_string(),
+            public_key: MINISIGN_PUBLIC_KEY.to_string(),
+        };
+        assert!(verify_signature(&package_signature, &path, "pkg", MINISIGN_SIGNATURE).is_ok());
+
+        let package_signature = PackageSignature::Minisign {
+            signature_template: "{artifact}.minisig".to_string(),
+            public_key: format!(
+                "untrusted comment: minisign public key E7620F1842B4E81F\n{}",
+                MINISIGN_PUBLIC_KEY
+            ),
+        };
+        assert!(verify_signature(&package_signature, &path, "pkg", MINISIGN_SIGNATURE).is_ok());
+
+        let path = create_file(dir.path(), b"tampered");
+        assert!(verify_signature(&package_signature, &path, "pkg", MINISIGN_SIGNATURE).is_err());
+        assert!(verify_signature(&package_signature, &path, "pkg", "invalid").is_err());
+    }
+}
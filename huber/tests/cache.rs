@@ -0,0 +1,21 @@
+use scopeguard::defer;
+use sequential_test::sequential;
+
+use crate::common::reset_huber;
+
+#[macro_use]
+mod common;
+
+#[test]
+#[sequential]
+fn test_cache_clear_list() {
+    defer! {
+        reset_huber();
+    }
+
+    let assert = huber_cmd!(arg("cache").arg("clear").assert().success());
+    assert_contain_line_regex!(assert.get_output().stderr, "Artifact cache cleared");
+
+    let assert = huber_cmd!(arg("cache").arg("list").assert().success());
+    assert_contain_line_regex!(assert.get_output().stderr, "No cached artifacts");
+}
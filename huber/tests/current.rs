@@ -1,7 +1,9 @@
 use scopeguard::defer;
 use sequential_test::sequential;
 
-use crate::common::{install_pkgs, reset_huber, INVALID_PKG, INVALID_PKG_VERSION, PKG_VERSION_1};
+use crate::common::{
+    install_pkgs, reset_huber, INVALID_PKG, INVALID_PKG_VERSION, PKG_VERSION_1, PKG_VERSION_2,
+};
 
 #[macro_use]
 mod common;
@@ -22,6 +24,27 @@ fn test_current() {
     );
 }
 
+#[test]
+#[sequential]
+fn test_current_version_requirement() {
+    defer! {
+        reset_huber();
+    }
+
+    install_pkgs(&[PKG_VERSION_1]);
+    install_pkgs(&[PKG_VERSION_2]);
+
+    let pkg = PKG_VERSION_1.splitn(2, '@').collect::<Vec<_>>()[0];
+    let assert = huber_cmd!(arg("current")
+        .arg(format!("{}@<0.32.7", pkg))
+        .assert()
+        .success());
+    assert_eq_last_line_regex!(
+        assert.get_output().stderr,
+        &format!(r#"{} is now the current version"#, PKG_VERSION_1)
+    );
+}
+
 #[test]
 #[sequential]
 fn test_current_fail() {
@@ -0,0 +1,43 @@
+use scopeguard::defer;
+use sequential_test::sequential;
+
+use crate::common::reset_huber;
+
+#[macro_use]
+mod common;
+
+#[cfg(not(target_os = "windows"))]
+#[test]
+#[sequential]
+fn test_doctor_fix_dangling_executable() {
+    defer! {
+        reset_huber();
+    }
+
+    let bin_dir = dirs::home_dir().unwrap().join(".huber").join("bin");
+    std::fs::create_dir_all(&bin_dir).unwrap();
+    let exec_link = bin_dir.join("dangling");
+    let _ = std::fs::remove_file(&exec_link);
+    std::os::unix::fs::symlink(bin_dir.join("not-found"), &exec_link).unwrap();
+
+    let path = std::env::join_paths(
+        std::iter::once(bin_dir.clone()).chain(std::env::split_paths(
+            &std::env::var_os("PATH").unwrap_or_default(),
+        )),
+    )
+    .unwrap();
+
+    let assert = huber_cmd!(env("PATH", &path).arg("doctor").assert().failure());
+    assert_contain_line_regex!(assert.get_output().stderr, r"Problems found: 1");
+    assert!(exec_link.symlink_metadata().is_ok());
+
+    huber_cmd!(env("PATH", &path)
+        .arg("doctor")
+        .arg("--fix")
+        .assert()
+        .success());
+    assert!(exec_link.symlink_metadata().is_err());
+
+    let assert = huber_cmd!(env("PATH", &path).arg("doctor").assert().success());
+    assert_contain_line_regex!(assert.get_output().stderr, r"No problems found");
+}
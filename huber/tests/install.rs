@@ -182,3 +182,37 @@ fn test_install_fail() {
 
     assert_contain_line_regex!(assert.get_output().stderr, &format!(r#"{} not found"#, pkg));
 }
+
+#[test]
+#[sequential]
+fn test_install_offline_fail() {
+    defer! {
+        reset_huber();
+    }
+
+    let assert = huber_cmd!(arg("--offline")
+        .arg("install")
+        .arg("k9s@v0.0.0")
+        .assert()
+        .failure());
+
+    assert_contain_line_regex!(
+        assert.get_output().stderr,
+        r#"Release not available in offline mode: "k9s(@v0.0.0)?""#
+    );
+}
+
+#[test]
+#[sequential]
+fn test_install_version_requirement() {
+    defer! {
+        reset_huber();
+    }
+
+    let pkg = PKG_VERSION_1.splitn(2, '@').collect::<Vec<_>>()[0];
+    let assert = install_pkgs(&[&format!("{}@~0.32", pkg)]);
+    assert_contain_line_regex!(
+        assert.get_output().stderr,
+        &format!(r"{}@v0\.32\.\d+ installed", pkg)
+    );
+}
@@ -0,0 +1,21 @@
+use scopeguard::defer;
+use sequential_test::sequential;
+
+use crate::common::reset_huber;
+
+#[macro_use]
+mod common;
+
+#[test]
+#[sequential]
+fn test_mirror_offline_fail() {
+    defer! {
+        reset_huber();
+    }
+
+    let assert = huber_cmd!(arg("--offline").arg("mirror").arg("k9s").assert().failure());
+    assert_contain_line_regex!(
+        assert.get_output().stderr,
+        "Not supported in offline mode: mirroring packages"
+    );
+}
@@ -0,0 +1,21 @@
+use scopeguard::defer;
+use sequential_test::sequential;
+
+use crate::common::{install_pkgs, reset_huber, PKG_VERSION_1};
+
+#[macro_use]
+mod common;
+
+#[test]
+#[sequential]
+fn test_outdated() {
+    defer! {
+        reset_huber();
+    }
+
+    install_pkgs(&[PKG_VERSION_1]);
+    let tokens: Vec<_> = PKG_VERSION_1.splitn(2, "@").collect();
+
+    let assert = huber_cmd!(arg("outdated").arg(tokens[0]).assert().code(2));
+    assert_contain_line_regex!(assert.get_output().stdout, tokens[1]);
+}
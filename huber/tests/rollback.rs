@@ -0,0 +1,45 @@
+use scopeguard::defer;
+use sequential_test::sequential;
+
+use crate::common::{install_pkgs, reset_huber, INVALID_PKG, PKG_VERSION_1, PKG_VERSION_2};
+
+#[macro_use]
+mod common;
+
+#[test]
+#[sequential]
+fn test_rollback() {
+    defer! {
+        reset_huber();
+    }
+
+    install_pkgs(&[PKG_VERSION_1]);
+    install_pkgs(&[PKG_VERSION_2]);
+
+    let pkg = PKG_VERSION_1.splitn(2, '@').collect::<Vec<_>>()[0];
+    let assert = huber_cmd!(arg("rollback").arg(pkg).assert().success());
+    assert_eq_last_line_regex!(
+        assert.get_output().stderr,
+        &format!(r#"{} is now the current version"#, PKG_VERSION_1)
+    );
+
+    let assert = huber_cmd!(arg("rollback").arg(pkg).assert().failure());
+    assert_contain_line_regex!(
+        assert.get_output().stderr,
+        &format!(r#"No previous version to roll back: "{}""#, pkg)
+    );
+}
+
+#[test]
+#[sequential]
+fn test_rollback_fail() {
+    defer! {
+        reset_huber();
+    }
+
+    let assert = huber_cmd!(arg("rollback").arg(INVALID_PKG).assert().failure());
+    assert_contain_line_regex!(
+        assert.get_output().stderr,
+        &format!(r#"Package not installed: "{}""#, INVALID_PKG)
+    );
+}
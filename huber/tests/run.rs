@@ -0,0 +1,72 @@
+use scopeguard::defer;
+use sequential_test::sequential;
+
+use crate::common::{
+    install_pkgs, reset_huber, uninstall_pkg, INVALID_PKG, PKG_VERSION_1, PKG_VERSION_2,
+};
+
+#[macro_use]
+mod common;
+
+#[test]
+#[sequential]
+fn test_run() {
+    defer! {
+        reset_huber();
+    }
+
+    install_pkgs(&[PKG_VERSION_2]);
+
+    let assert = huber_cmd!(arg("run")
+        .arg(PKG_VERSION_1)
+        .arg("--")
+        .arg("version")
+        .arg("--short")
+        .assert()
+        .success());
+    let version = PKG_VERSION_1.splitn(2, '@').last().unwrap();
+    assert_contain_line_regex!(assert.get_output().stdout, version);
+
+    let pkg = PKG_VERSION_2.splitn(2, '@').collect::<Vec<_>>()[0];
+    let assert = huber_cmd!(arg("show").arg(pkg).assert().success());
+    assert_contain_line_regex!(
+        assert.get_output().stdout,
+        &format!(r"{}\s+true", PKG_VERSION_2.splitn(2, '@').last().unwrap())
+    );
+}
+
+#[test]
+#[sequential]
+fn test_run_uninstall() {
+    defer! {
+        reset_huber();
+    }
+
+    huber_cmd!(arg("run")
+        .arg(PKG_VERSION_1)
+        .arg("--")
+        .arg("version")
+        .arg("--short")
+        .assert()
+        .success());
+
+    // uninstalled, although not set as current
+    let pkg = PKG_VERSION_1.splitn(2, '@').collect::<Vec<_>>()[0];
+    let assert = uninstall_pkg(pkg);
+    assert_contain_line_regex!(assert.get_output().stderr, &format!("Uninstalled {}", pkg));
+    huber_cmd!(arg("show").arg(pkg).assert().failure());
+}
+
+#[test]
+#[sequential]
+fn test_run_fail() {
+    defer! {
+        reset_huber();
+    }
+
+    let assert = huber_cmd!(arg("run").arg(INVALID_PKG).assert().failure());
+    assert_contain_line_regex!(
+        assert.get_output().stderr,
+        &format!(r#"Package not found: "{}""#, INVALID_PKG)
+    );
+}
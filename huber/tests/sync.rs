@@ -0,0 +1,45 @@
+use std::fs;
+
+use scopeguard::defer;
+use sequential_test::sequential;
+
+use crate::common::{install_pkgs, reset_huber, PKG_VERSION_2};
+
+#[macro_use]
+mod common;
+
+#[test]
+#[sequential]
+fn test_sync() {
+    defer! {
+        reset_huber();
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join("huber.yaml");
+    let lock = dir.path().join("huber.lock");
+    fs::write(&manifest, "packages:\n  k9s: ~0.32.5\n").unwrap();
+
+    install_pkgs(&[PKG_VERSION_2]);
+
+    let assert = huber_cmd!(arg("sync").arg("--file").arg(&manifest).assert().success());
+    assert_contain_line_regex!(assert.get_output().stderr, r#"Packages synced: total 1"#);
+
+    let content = fs::read_to_string(&lock).unwrap();
+    assert!(content.contains("name: k9s"));
+
+    huber_cmd!(arg("sync")
+        .arg("--file")
+        .arg(&manifest)
+        .arg("--locked")
+        .assert()
+        .success());
+
+    fs::write(&manifest, "packages:\n  k9s: 0.32.5\n").unwrap();
+    huber_cmd!(arg("sync")
+        .arg("--file")
+        .arg(&manifest)
+        .arg("--locked")
+        .assert()
+        .failure());
+}